@@ -0,0 +1,262 @@
+use std::fmt;
+
+/// Integer-like values an [`Interval`] can be made of.
+pub trait Discrete: Copy + Ord {
+    /// The next value, or `None` if `self` is the maximum.
+    fn succ(self) -> Option<Self>;
+    /// The previous value, or `None` if `self` is the minimum.
+    fn pred(self) -> Option<Self>;
+    /// Number of steps from `self` up to `other`, saturating at `u64::MAX`.
+    fn distance(self, other: Self) -> u64;
+}
+
+macro_rules! impl_discrete {
+    ($($t:ty),*) => {
+        $(
+            impl Discrete for $t {
+                fn succ(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn pred(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn distance(self, other: Self) -> u64 {
+                    u64::try_from(other as i128 - self as i128).unwrap_or(u64::MAX)
+                }
+            }
+        )*
+    };
+}
+
+impl_discrete!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// A closed range `start..=end` of discrete values.
+///
+/// An interval whose end is before its start is empty, and all empty intervals are equal,
+/// so set operations never have to special-case inverted bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval<T: Discrete> {
+    bounds: Option<(T, T)>,
+}
+
+impl<T: Discrete> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        if end < start {
+            return Self::empty();
+        }
+        Self {
+            bounds: Some((start, end)),
+        }
+    }
+
+    pub fn empty() -> Self {
+        Self { bounds: None }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bounds.is_none()
+    }
+
+    pub fn start(&self) -> Option<T> {
+        self.bounds.map(|(start, _)| start)
+    }
+
+    pub fn end(&self) -> Option<T> {
+        self.bounds.map(|(_, end)| end)
+    }
+
+    /// Number of values in the interval, saturating at `u64::MAX`.
+    pub fn len(&self) -> u64 {
+        self.bounds
+            .map_or(0, |(start, end)| start.distance(end).saturating_add(1))
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.bounds
+            .is_some_and(|(start, end)| start <= *value && *value <= end)
+    }
+
+    /// Whether every value of `other` is in `self`. The empty interval is contained in
+    /// everything.
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        match (self.bounds, other.bounds) {
+            (_, None) => true,
+            (None, Some(_)) => false,
+            (Some((start, end)), Some((other_start, other_end))) => {
+                start <= other_start && other_end <= end
+            }
+        }
+    }
+
+    /// Whether the intervals share at least one value.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    /// Whether the intervals do not overlap but there is no value between them.
+    pub fn is_adjacent(&self, other: &Self) -> bool {
+        match (self.bounds, other.bounds) {
+            (Some((start, end)), Some((other_start, other_end))) => {
+                end.succ() == Some(other_start) || other_end.succ() == Some(start)
+            }
+            _ => false,
+        }
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        match (self.bounds, other.bounds) {
+            (Some((start, end)), Some((other_start, other_end))) => {
+                Self::new(start.max(other_start), end.min(other_end))
+            }
+            _ => Self::empty(),
+        }
+    }
+
+    /// The smallest interval containing both.
+    pub fn hull(&self, other: &Self) -> Self {
+        match (self.bounds, other.bounds) {
+            (None, _) => *other,
+            (_, None) => *self,
+            (Some((start, end)), Some((other_start, other_end))) => {
+                Self::new(start.min(other_start), end.max(other_end))
+            }
+        }
+    }
+
+    /// The values in either interval, as one interval when they overlap or touch and as
+    /// two sorted intervals otherwise. Empty intervals are left out.
+    pub fn union(&self, other: &Self) -> Vec<Self> {
+        if self.is_empty() || other.is_empty() || self.overlaps(other) || self.is_adjacent(other) {
+            return [self.hull(other)]
+                .into_iter()
+                .filter(|interval| !interval.is_empty())
+                .collect();
+        }
+        let mut res = vec![*self, *other];
+        res.sort_by_key(|interval| interval.start());
+        res
+    }
+
+    /// The values of `self` that are not in `other`: nothing, one interval, or the two
+    /// pieces left on either side when `other` is strictly inside `self`.
+    pub fn difference(&self, other: &Self) -> Vec<Self> {
+        let common = self.intersection(other);
+        let (Some((start, end)), Some((common_start, common_end))) = (self.bounds, common.bounds)
+        else {
+            return [*self]
+                .into_iter()
+                .filter(|interval| !interval.is_empty())
+                .collect();
+        };
+        let before = common_start
+            .pred()
+            .map_or(Self::empty(), |pred| Self::new(start, pred));
+        let after = common_end
+            .succ()
+            .map_or(Self::empty(), |succ| Self::new(succ, end));
+        [before, after]
+            .into_iter()
+            .filter(|interval| !interval.is_empty())
+            .collect()
+    }
+}
+
+impl<T: Discrete + fmt::Display> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.bounds {
+            Some((start, end)) => write!(f, "{}-{}", start, end),
+            None => write!(f, "(empty)"),
+        }
+    }
+}
+
+/// A set of values stored as sorted, disjoint and non-adjacent intervals, so that any two
+/// inserted intervals that overlap or touch are merged.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IntervalSet<T: Discrete> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Discrete> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        // every interval that can merge with the new one is in a contiguous run
+        let first = self.intervals.partition_point(|existing| {
+            existing.end() < interval.start() && !existing.is_adjacent(&interval)
+        });
+        let mut merged = interval;
+        let mut last = first;
+        while last < self.intervals.len()
+            && (self.intervals[last].overlaps(&merged) || self.intervals[last].is_adjacent(&merged))
+        {
+            merged = merged.hull(&self.intervals[last]);
+            last += 1;
+        }
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of values in the set, saturating at `u64::MAX`.
+    pub fn len(&self) -> u64 {
+        self.intervals
+            .iter()
+            .fold(0u64, |total, interval| total.saturating_add(interval.len()))
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        let idx = self
+            .intervals
+            .partition_point(|interval| interval.end() < Some(*value));
+        self.intervals
+            .get(idx)
+            .is_some_and(|interval| interval.contains(value))
+    }
+
+    /// The smallest interval containing the whole set.
+    pub fn hull(&self) -> Interval<T> {
+        match (self.intervals.first(), self.intervals.last()) {
+            (Some(first), Some(last)) => first.hull(last),
+            _ => Interval::empty(),
+        }
+    }
+
+    /// The values between the first and last interval that are not in the set.
+    pub fn gaps(&self) -> Vec<Interval<T>> {
+        self.intervals
+            .windows(2)
+            .filter_map(|pair| {
+                // the intervals never touch, so there is always a value on either side
+                let start = pair[0].end()?.succ()?;
+                let end = pair[1].start()?.pred()?;
+                Some(Interval::new(start, end))
+            })
+            .collect()
+    }
+}
+
+impl<T: Discrete> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
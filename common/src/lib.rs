@@ -1,3 +1,6 @@
+pub mod interval;
+pub mod rng;
+
 use std::{
     fs::File,
     io::{self, BufRead},
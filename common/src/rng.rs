@@ -0,0 +1,54 @@
+/// Small deterministic pseudo-random generator (SplitMix64), so that simulations
+/// and generated inputs can be reproduced from a seed without external crates.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a value in `0..bound`. `bound` must be non-zero.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "bound must be non-zero");
+        // rejection sampling to avoid modulo bias
+        let zone = u64::MAX - (u64::MAX % bound);
+        loop {
+            let val = self.next_u64();
+            if val < zone {
+                return val % bound;
+            }
+        }
+    }
+
+    /// Returns a value in the inclusive range `low..=high`.
+    pub fn range_inclusive(&mut self, low: u64, high: u64) -> u64 {
+        assert!(low <= high, "empty range");
+        if low == 0 && high == u64::MAX {
+            return self.next_u64();
+        }
+        low + self.below(high - low + 1)
+    }
+
+    /// Returns a float in `0.0..1.0`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+        items.get(self.below(items.len() as u64) as usize)
+    }
+}
@@ -1,5 +1,9 @@
+use std::{env, process};
+
 use common::{Parsable, ParseStatus};
 
+mod tournament;
+
 #[derive(Debug, PartialEq, Clone, Copy)]
 enum Choice {
     Rock,
@@ -7,6 +11,7 @@ enum Choice {
     Scissors,
 }
 
+#[derive(Debug, PartialEq, Clone, Copy)]
 enum RoundResult {
     Win,
     Lose,
@@ -20,6 +25,16 @@ struct Round {
 }
 
 impl Choice {
+    const ALL: [Choice; 3] = [Choice::Rock, Choice::Paper, Choice::Scissors];
+
+    fn index(&self) -> usize {
+        match self {
+            Choice::Rock => 0,
+            Choice::Paper => 1,
+            Choice::Scissors => 2,
+        }
+    }
+
     fn from_string(s: &str) -> Self {
         if s == "A" || s == "X" {
             return Choice::Rock;
@@ -121,4 +136,25 @@ fn main() {
         "{:?}",
         rounds.iter().map(|round| round.score()).sum::<i64>()
     );
+
+    // day2 tournament [rounds per match] [seed]
+    let args = env::args().collect::<Vec<_>>();
+    if args.get(1).map(String::as_str) == Some("tournament") {
+        let usage = || -> ! {
+            eprintln!("usage: day2 tournament [rounds per match] [seed]");
+            process::exit(1);
+        };
+        let rounds_per_match = args
+            .get(2)
+            .map_or(Ok(1000), |s| s.parse())
+            .unwrap_or_else(|_| usage());
+        let seed = args
+            .get(3)
+            .map_or(Ok(2022), |s| s.parse())
+            .unwrap_or_else(|_| usage());
+        let guide = rounds.iter().map(|round| round.choice).collect::<Vec<_>>();
+        let entrants = tournament::default_entrants(seed, guide);
+        let leaderboard = tournament::round_robin(&entrants, rounds_per_match);
+        print!("{}", leaderboard);
+    }
 }
@@ -0,0 +1,288 @@
+use std::fmt;
+
+use common::rng::Rng;
+
+use crate::{Choice, Round, RoundResult};
+
+/// A rock-paper-scissors player. A fresh instance is created for every match, and
+/// it is told what both sides played after each round.
+pub trait Strategy {
+    fn choose(&mut self) -> Choice;
+    fn observe(&mut self, _own: Choice, _opponent: Choice) {}
+}
+
+/// Always plays the same choice.
+pub struct Fixed(pub Choice);
+
+impl Strategy for Fixed {
+    fn choose(&mut self) -> Choice {
+        self.0
+    }
+}
+
+/// Plays uniformly at random, reproducible from the seed.
+pub struct Random {
+    rng: Rng,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+        }
+    }
+}
+
+impl Strategy for Random {
+    fn choose(&mut self) -> Choice {
+        *self.rng.choose(&Choice::ALL).unwrap()
+    }
+}
+
+/// Predicts the opponent will repeat their most frequent choice so far, and plays
+/// whatever beats it.
+#[derive(Default)]
+pub struct FrequencyCounter {
+    counts: [usize; 3],
+}
+
+impl Strategy for FrequencyCounter {
+    fn choose(&mut self) -> Choice {
+        most_likely(&self.counts).map_or(Choice::Rock, |predicted| predicted.loses_against())
+    }
+
+    fn observe(&mut self, _own: Choice, opponent: Choice) {
+        self.counts[opponent.index()] += 1;
+    }
+}
+
+/// Predicts the opponent's next choice from a first-order Markov chain over their
+/// previous choices, and plays whatever beats it.
+#[derive(Default)]
+pub struct MarkovPredictor {
+    last_opponent: Option<Choice>,
+    transitions: [[usize; 3]; 3],
+}
+
+impl Strategy for MarkovPredictor {
+    fn choose(&mut self) -> Choice {
+        self.last_opponent
+            .and_then(|last| most_likely(&self.transitions[last.index()]))
+            .map_or(Choice::Rock, |predicted| predicted.loses_against())
+    }
+
+    fn observe(&mut self, _own: Choice, opponent: Choice) {
+        if let Some(last) = self.last_opponent {
+            self.transitions[last.index()][opponent.index()] += 1;
+        }
+        self.last_opponent = Some(opponent);
+    }
+}
+
+/// Replays the choices of a strategy guide in order, starting over once it runs out.
+pub struct Replay {
+    guide: Vec<Choice>,
+    position: usize,
+}
+
+impl Replay {
+    pub fn new(guide: Vec<Choice>) -> Self {
+        assert!(!guide.is_empty(), "cannot replay an empty strategy guide");
+        Self { guide, position: 0 }
+    }
+}
+
+impl Strategy for Replay {
+    fn choose(&mut self) -> Choice {
+        let choice = self.guide[self.position % self.guide.len()];
+        self.position += 1;
+        choice
+    }
+}
+
+// the choice with the highest count, ties going to the first in `Choice::ALL`
+fn most_likely(counts: &[usize; 3]) -> Option<Choice> {
+    Choice::ALL
+        .iter()
+        .rev()
+        .max_by_key(|choice| counts[choice.index()])
+        .filter(|choice| counts[choice.index()] > 0)
+        .copied()
+}
+
+pub struct Entrant {
+    pub name: String,
+    pub make: Box<dyn Fn() -> Box<dyn Strategy>>,
+}
+
+impl Entrant {
+    pub fn new<F>(name: &str, make: F) -> Self
+    where
+        F: Fn() -> Box<dyn Strategy> + 'static,
+    {
+        Self {
+            name: name.into(),
+            make: Box::new(make),
+        }
+    }
+}
+
+pub fn default_entrants(seed: u64, guide: Vec<Choice>) -> Vec<Entrant> {
+    let mut entrants = Choice::ALL
+        .iter()
+        .map(|&choice| {
+            Entrant::new(&format!("always {:?}", choice), move || {
+                Box::new(Fixed(choice))
+            })
+        })
+        .collect::<Vec<_>>();
+    entrants.push(Entrant::new(
+        &format!("random (seed {})", seed),
+        move || Box::new(Random::new(seed)),
+    ));
+    entrants.push(Entrant::new("frequency counter", || {
+        Box::<FrequencyCounter>::default()
+    }));
+    entrants.push(Entrant::new("markov predictor", || {
+        Box::<MarkovPredictor>::default()
+    }));
+    // an empty guide has nothing to replay, so it does not enter
+    if !guide.is_empty() {
+        entrants.push(Entrant::new("strategy guide", move || {
+            Box::new(Replay::new(guide.clone()))
+        }));
+    }
+    entrants
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Standing {
+    pub name: String,
+    pub score: i64,
+    pub rounds_won: usize,
+    pub rounds_lost: usize,
+    pub rounds_drawn: usize,
+    pub matches_won: usize,
+    pub matches_lost: usize,
+    pub matches_drawn: usize,
+}
+
+impl Standing {
+    fn record_round(&mut self, round: &Round) {
+        self.score += round.score();
+        match round.result() {
+            RoundResult::Win => self.rounds_won += 1,
+            RoundResult::Lose => self.rounds_lost += 1,
+            RoundResult::Draw => self.rounds_drawn += 1,
+        }
+    }
+}
+
+pub struct Leaderboard {
+    pub standings: Vec<Standing>,
+    pub rounds_per_match: usize,
+}
+
+/// Plays a match of `rounds` rounds, returning the total score of each side.
+pub fn play_match(
+    first: &mut dyn Strategy,
+    second: &mut dyn Strategy,
+    rounds: usize,
+    standings: (&mut Standing, &mut Standing),
+) -> (i64, i64) {
+    let (first_standing, second_standing) = standings;
+    let mut totals = (0i64, 0i64);
+    for _ in 0..rounds {
+        let first_choice = first.choose();
+        let second_choice = second.choose();
+        let first_round = Round {
+            choice: first_choice,
+            opponent: second_choice,
+        };
+        let second_round = Round {
+            choice: second_choice,
+            opponent: first_choice,
+        };
+        totals.0 += first_round.score();
+        totals.1 += second_round.score();
+        first_standing.record_round(&first_round);
+        second_standing.record_round(&second_round);
+        first.observe(first_choice, second_choice);
+        second.observe(second_choice, first_choice);
+    }
+    totals
+}
+
+/// Every entrant plays every other entrant once; a match is won by the higher total score.
+pub fn round_robin(entrants: &[Entrant], rounds_per_match: usize) -> Leaderboard {
+    let mut standings = entrants
+        .iter()
+        .map(|entrant| Standing {
+            name: entrant.name.clone(),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+
+    for first_idx in 0..entrants.len() {
+        for second_idx in first_idx + 1..entrants.len() {
+            let mut first = (entrants[first_idx].make)();
+            let mut second = (entrants[second_idx].make)();
+            let (head, tail) = standings.split_at_mut(second_idx);
+            let (first_total, second_total) = play_match(
+                first.as_mut(),
+                second.as_mut(),
+                rounds_per_match,
+                (&mut head[first_idx], &mut tail[0]),
+            );
+            if first_total > second_total {
+                standings[first_idx].matches_won += 1;
+                standings[second_idx].matches_lost += 1;
+            } else if first_total < second_total {
+                standings[first_idx].matches_lost += 1;
+                standings[second_idx].matches_won += 1;
+            } else {
+                standings[first_idx].matches_drawn += 1;
+                standings[second_idx].matches_drawn += 1;
+            }
+        }
+    }
+
+    standings.sort_by(|a, b| {
+        b.matches_won
+            .cmp(&a.matches_won)
+            .then(b.score.cmp(&a.score))
+    });
+    Leaderboard {
+        standings,
+        rounds_per_match,
+    }
+}
+
+impl fmt::Display for Leaderboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "round robin, {} rounds per match", self.rounds_per_match)?;
+        writeln!(
+            f,
+            "{:>2}  {:<24} {:>10} {:>13} {:>19}",
+            "#", "strategy", "score", "matches W/L/D", "rounds W/L/D"
+        )?;
+        for (rank, standing) in self.standings.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>2}  {:<24} {:>10} {:>13} {:>19}",
+                rank + 1,
+                standing.name,
+                standing.score,
+                format!(
+                    "{}/{}/{}",
+                    standing.matches_won, standing.matches_lost, standing.matches_drawn
+                ),
+                format!(
+                    "{}/{}/{}",
+                    standing.rounds_won, standing.rounds_lost, standing.rounds_drawn
+                ),
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+use std::{env, fs, hint::black_box, time::Instant};
+
+use common::rng::Rng;
+use day3::{
+    items::{common_items_as, BitItemSet, HashItemSet, ItemSet},
+    priority::PriorityTable,
+};
+
+const ALPHABET: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// each rucksack shares exactly one item between its compartments
+fn generate_rucksacks(rng: &mut Rng, count: usize, compartment_len: usize) -> String {
+    let mut contents = String::new();
+    let mut alphabet = ALPHABET.chars().collect::<Vec<_>>();
+    for _ in 0..count {
+        for idx in (1..alphabet.len()).rev() {
+            alphabet.swap(idx, rng.below(idx as u64 + 1) as usize);
+        }
+        let shared = alphabet[0];
+        let (left_pool, right_pool) = alphabet[1..].split_at(25);
+        for pool in [left_pool, right_pool] {
+            let shared_at = rng.below(compartment_len as u64) as usize;
+            for idx in 0..compartment_len {
+                if idx == shared_at {
+                    contents.push(shared);
+                } else {
+                    contents.push(*rng.choose(pool).unwrap());
+                }
+            }
+        }
+        contents.push('\n');
+    }
+    contents
+}
+
+fn time<F: FnMut() -> i64>(name: &str, mut f: F) -> i64 {
+    let start = Instant::now();
+    let res = black_box(f());
+    println!("{:<28} {:>12?}  (result {})", name, start.elapsed(), res);
+    res
+}
+
+fn main() {
+    let count = env::args()
+        .skip(1)
+        .find_map(|arg| arg.parse::<usize>().ok())
+        .unwrap_or(300_000);
+    let path = env::temp_dir().join(format!("day3_rucksacks_{}.txt", count));
+    fs::write(&path, generate_rucksacks(&mut Rng::new(3), count, 24)).unwrap();
+    let contents = fs::read_to_string(&path).unwrap();
+    let lines = contents.lines().collect::<Vec<_>>();
+    println!("{} rucksacks from {}", lines.len(), path.display());
+
+    let table = PriorityTable::default();
+    let halves = |line: &&str| {
+        let (left, right) = line.split_at(line.len() / 2);
+        [left.to_owned(), right.to_owned()]
+    };
+    let compartments = lines.iter().map(halves).collect::<Vec<_>>();
+
+    let hash_compartments = time("compartments, HashSet", || {
+        compartments
+            .iter()
+            .flat_map(|pair| common_items_as::<HashItemSet, _, _>(pair).unwrap())
+            .map(|item| table.priority(item).unwrap())
+            .sum()
+    });
+    let bit_compartments = time("compartments, u64 bitset", || {
+        compartments
+            .iter()
+            .map(|[left, right]| {
+                let left = BitItemSet::from_items(left).unwrap();
+                let right = BitItemSet::from_items(right).unwrap();
+                left.intersection(&right).default_priority_sum()
+            })
+            .sum()
+    });
+    assert_eq!(hash_compartments, bit_compartments);
+
+    let hash_groups = time("groups of 3, HashSet", || {
+        lines
+            .chunks(3)
+            .flat_map(|group| common_items_as::<HashItemSet, _, _>(group).unwrap())
+            .map(|item| table.priority(item).unwrap())
+            .sum()
+    });
+    let bit_groups = time("groups of 3, u64 bitset", || {
+        lines
+            .chunks(3)
+            .map(|group| {
+                group
+                    .iter()
+                    .map(|items| BitItemSet::from_items(items).unwrap())
+                    .fold(BitItemSet(u64::MAX), |accum, set| accum.intersection(&set))
+                    .default_priority_sum()
+            })
+            .sum()
+    });
+    assert_eq!(hash_groups, bit_groups);
+
+    fs::remove_file(&path).unwrap();
+}
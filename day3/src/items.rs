@@ -0,0 +1,146 @@
+use std::collections::{BTreeSet, HashSet};
+
+use crate::priority::{PriorityError, PriorityTable};
+
+/// A set of rucksack items that can be intersected with others of its kind.
+pub trait ItemSet: Sized {
+    /// Builds the set, or returns the first item it cannot represent.
+    fn from_items(items: &str) -> Result<Self, char>;
+    fn intersection(&self, other: &Self) -> Self;
+    fn to_items(&self) -> BTreeSet<char>;
+}
+
+/// Works for any item, at the cost of hashing every character.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HashItemSet(pub HashSet<char>);
+
+impl ItemSet for HashItemSet {
+    fn from_items(items: &str) -> Result<Self, char> {
+        Ok(HashItemSet(items.chars().collect()))
+    }
+
+    fn intersection(&self, other: &Self) -> Self {
+        HashItemSet(self.0.intersection(&other.0).copied().collect())
+    }
+
+    fn to_items(&self) -> BTreeSet<char> {
+        self.0.iter().copied().collect()
+    }
+}
+
+/// Only holds the 52 items of the AoC alphabet, with bit `n` set for the item of
+/// priority `n + 1`, so intersection is a single AND.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BitItemSet(pub u64);
+
+impl BitItemSet {
+    fn bit(item: char) -> Option<u32> {
+        match item {
+            'a'..='z' => Some(item as u32 - 'a' as u32),
+            'A'..='Z' => Some(item as u32 - 'A' as u32 + 26),
+            _ => None,
+        }
+    }
+
+    fn item(bit: u32) -> char {
+        if bit < 26 {
+            (b'a' + bit as u8) as char
+        } else {
+            (b'A' + (bit - 26) as u8) as char
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Priority of the lowest-priority item in the set under the default AoC priorities,
+    /// which match the bit order. See [`BitItemSet::priority_sum_in`] for other tables.
+    pub fn default_lowest_priority(&self) -> Option<i64> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(self.0.trailing_zeros() as i64 + 1)
+    }
+
+    /// Sum of the priorities of all items in the set under the default AoC priorities,
+    /// straight from the bit positions.
+    pub fn default_priority_sum(&self) -> i64 {
+        let mut bits = self.0;
+        let mut sum = 0;
+        while bits != 0 {
+            sum += bits.trailing_zeros() as i64 + 1;
+            bits &= bits - 1;
+        }
+        sum
+    }
+
+    /// Sum of the priorities of all items in the set under `table`.
+    pub fn priority_sum_in(&self, table: &PriorityTable) -> Result<i64, PriorityError> {
+        self.to_items()
+            .into_iter()
+            .map(|item| table.priority(item))
+            .sum()
+    }
+}
+
+impl ItemSet for BitItemSet {
+    fn from_items(items: &str) -> Result<Self, char> {
+        let mut bits = 0u64;
+        for item in items.chars() {
+            bits |= 1 << Self::bit(item).ok_or(item)?;
+        }
+        Ok(BitItemSet(bits))
+    }
+
+    fn intersection(&self, other: &Self) -> Self {
+        BitItemSet(self.0 & other.0)
+    }
+
+    fn to_items(&self) -> BTreeSet<char> {
+        let mut bits = self.0;
+        let mut items = BTreeSet::new();
+        while bits != 0 {
+            items.insert(Self::item(bits.trailing_zeros()));
+            bits &= bits - 1;
+        }
+        items
+    }
+}
+
+/// Items that appear in every one of `sets`, using the given set representation.
+/// All shared items are returned, in sorted order, so the result does not depend on
+/// hashing when more than one is shared. No sets at all means there is nothing in common.
+pub fn common_items_as<Set, I, S>(sets: I) -> Result<BTreeSet<char>, char>
+where
+    Set: ItemSet,
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut common: Option<Set> = None;
+    for items in sets {
+        let set = Set::from_items(items.as_ref())?;
+        common = Some(match common {
+            Some(accum) => accum.intersection(&set),
+            None => set,
+        });
+    }
+    Ok(common.map(|set| set.to_items()).unwrap_or_default())
+}
+
+/// Same as [`common_items_as`], with a [`BitItemSet`] when every item is in the AoC
+/// alphabet and a [`HashItemSet`], which accepts any item, otherwise.
+pub fn common_items<I, S>(sets: I) -> BTreeSet<char>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let sets = sets.into_iter().collect::<Vec<_>>();
+    common_items_as::<BitItemSet, _, _>(&sets)
+        .or_else(|_| common_items_as::<HashItemSet, _, _>(&sets))
+        .unwrap()
+}
@@ -0,0 +1,2 @@
+pub mod items;
+pub mod priority;
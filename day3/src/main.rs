@@ -1,58 +1,191 @@
-use std::collections::HashSet;
+use std::{collections::BTreeSet, env, process};
 
 use common::{Parsable, ParseStatus};
 
-#[derive(Debug)]
+use day3::{
+    items::common_items,
+    priority::{PriorityError, PriorityTable},
+};
+
+mod repack;
+
+#[derive(Debug, Clone)]
 struct Rucksack {
     compartments: [String; 2],
 }
 
+/// A view over consecutive rucksacks, e.g. an elf group or a sliding window.
+#[derive(Debug)]
+struct Group<'a> {
+    rucksacks: &'a [Rucksack],
+}
+
 impl Parsable for Rucksack {
     fn parse_line(line: &str, curr_item: &mut Option<Rucksack>) -> ParseStatus {
-        let line_len = line.len();
+        // split on characters rather than bytes, so multi-byte items stay whole
+        let items = line.chars().collect::<Vec<_>>();
         let compartments = [
-            (&line[0..line_len / 2]).to_owned(),
-            (&line[line_len / 2..line_len]).to_owned(),
+            items[0..items.len() / 2].iter().collect(),
+            items[items.len() / 2..].iter().collect(),
         ];
         curr_item.replace(Rucksack { compartments });
         return ParseStatus::ItemComplete;
     }
 }
 
+// every item in `shared` contributes its priority, after checking all of `items` are known
+fn shared_priority<'a, I>(
+    table: &PriorityTable,
+    items: I,
+    shared: &BTreeSet<char>,
+) -> Result<i64, PriorityError>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    for item in items {
+        table.validate(item)?;
+    }
+    shared.iter().map(|&item| table.priority(item)).sum()
+}
+
 impl Rucksack {
-    fn in_both_compartments(&self) -> char {
-        let compartment_set: Vec<HashSet<char>> = self
-            .compartments
+    fn items(&self) -> String {
+        self.compartments.concat()
+    }
+
+    fn in_both_compartments(&self) -> BTreeSet<char> {
+        common_items(&self.compartments)
+    }
+
+    fn priority(&self, table: &PriorityTable) -> Result<i64, PriorityError> {
+        shared_priority(
+            table,
+            self.compartments.iter().map(String::as_str),
+            &self.in_both_compartments(),
+        )
+    }
+}
+
+impl<'a> Group<'a> {
+    fn badges(&self) -> BTreeSet<char> {
+        common_items(self.rucksacks.iter().map(Rucksack::items))
+    }
+
+    fn priority(&self, table: &PriorityTable) -> Result<i64, PriorityError> {
+        let items = self
+            .rucksacks
             .iter()
-            .map(|items| HashSet::from_iter(items.chars()))
+            .map(Rucksack::items)
             .collect::<Vec<_>>();
-        return compartment_set[0]
-            .intersection(&compartment_set[1])
-            .next()
-            .unwrap()
-            .to_owned();
+        shared_priority(table, items.iter().map(String::as_str), &self.badges())
     }
+}
 
-    fn priority(&self) -> i64 {
-        let val = self.in_both_compartments();
-        let ord_val = val as i64;
-
-        if val >= 'a' && val <= 'z' {
-            return ord_val - ('a' as i64) + 1;
-        } else {
-            return ord_val - ('A' as i64) + 27;
+/// The number following `name`, exiting with a message unless it is at least 1.
+fn arg_value(args: &[String], name: &str) -> Option<usize> {
+    let position = args.iter().position(|arg| arg == name)?;
+    let value = args.get(position + 1).map(String::as_str).unwrap_or("");
+    match value.parse() {
+        Ok(count) if count >= 1 => Some(count),
+        _ => {
+            eprintln!("{} needs a count of at least 1, got {:?}", name, value);
+            process::exit(1);
         }
     }
 }
 
+fn exit_on_error<T>(res: Result<T, PriorityError>) -> T {
+    res.unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    })
+}
+
+// priority sums of the compartment overlaps and of the group badges
+fn priority_sums(
+    rucksacks: &[Rucksack],
+    group_size: usize,
+    table: &PriorityTable,
+) -> Result<(i64, i64), PriorityError> {
+    let compartments = rucksacks
+        .iter()
+        .map(|rucksack| rucksack.priority(table))
+        .sum::<Result<i64, _>>()?;
+    let groups = rucksacks
+        .chunks(group_size)
+        .map(|rucksacks| Group { rucksacks }.priority(table))
+        .sum::<Result<i64, _>>()?;
+    Ok((compartments, groups))
+}
+
 fn main() {
+    // day3 [--group-size N] [--window N] [--repack] [--alphabet <items> | --priority-map <file>]
+    let args = env::args().collect::<Vec<_>>();
+    let table = exit_on_error(PriorityTable::from_args(&args));
+    let group_size = arg_value(&args, "--group-size").unwrap_or(3);
+
     let rucksacks = Rucksack::parse_file("input/day3.txt");
     println!("len rucksacks {:}", rucksacks.len());
-    println!(
-        "rucksacks priority {:?}",
-        rucksacks
-            .iter()
-            .map(|rucksack| rucksack.priority())
-            .sum::<i64>()
-    );
+    let priority = rucksacks
+        .iter()
+        .map(|rucksack| rucksack.priority(&table))
+        .sum::<Result<i64, _>>();
+    println!("rucksacks priority {:?}", exit_on_error(priority));
+
+    let groups = rucksacks
+        .chunks(group_size)
+        .map(|rucksacks| Group { rucksacks })
+        .collect::<Vec<_>>();
+    println!("len groups {:}", groups.len());
+    let priority = groups
+        .iter()
+        .map(|group| group.priority(&table))
+        .sum::<Result<i64, _>>();
+    println!("groups priority {:?}", exit_on_error(priority));
+
+    for (idx, rucksack) in rucksacks.iter().enumerate() {
+        let shared = rucksack.in_both_compartments();
+        if shared.len() != 1 {
+            println!("rucksack {} shares {:?}", idx, shared);
+        }
+    }
+    for (idx, group) in groups.iter().enumerate() {
+        let badges = group.badges();
+        if badges.len() != 1 {
+            println!("group {} shares {:?}", idx, badges);
+        }
+    }
+
+    if let Some(window) = arg_value(&args, "--window") {
+        for (idx, rucksacks) in rucksacks.windows(window).enumerate() {
+            let shared = Group { rucksacks }.badges();
+            if !shared.is_empty() {
+                println!("window {}..{} shares {:?}", idx, idx + window, shared);
+            }
+        }
+    }
+
+    if args.iter().any(|arg| arg == "--repack") {
+        let plan = repack::plan(&rucksacks, group_size);
+        for item_move in &plan.moves {
+            println!("{}", item_move);
+        }
+        for group in &plan.unresolved_groups {
+            println!("group {} cannot be given exactly one badge", group);
+        }
+        for &idx in &plan.unbalanced_rucksacks {
+            let [left, right] = &plan.rucksacks[idx].compartments;
+            println!(
+                "rucksack {} compartments now hold {} and {} items",
+                idx,
+                left.chars().count(),
+                right.chars().count()
+            );
+        }
+        let before = exit_on_error(priority_sums(&rucksacks, group_size, &table));
+        let after = exit_on_error(priority_sums(&plan.rucksacks, group_size, &table));
+        println!("{} moves of {} items", plan.moves.len(), plan.item_moves());
+        println!("compartment priority before {} after {}", before.0, after.0);
+        println!("group priority before {} after {}", before.1, after.1);
+    }
 }
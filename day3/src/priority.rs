@@ -0,0 +1,148 @@
+use std::{collections::HashMap, fmt, fs, path::Path, str::FromStr};
+
+/// Maps rucksack item types to their priorities.
+///
+/// The default table is the AoC alphabet (`a..=z` are 1 through 26, `A..=Z` are 27
+/// through 52), but any ordering of unicode characters, or an explicit map, can be used.
+#[derive(Debug, Clone)]
+pub struct PriorityTable {
+    priorities: HashMap<char, i64>,
+}
+
+#[derive(Debug)]
+pub enum PriorityError {
+    UnknownItem(char),
+    DuplicateItem(char),
+    InvalidMapLine {
+        line_number: usize,
+        line: String,
+    },
+    Io(String),
+    /// A command line option given without its value.
+    MissingValue(&'static str),
+}
+
+impl fmt::Display for PriorityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PriorityError::UnknownItem(c) => write!(f, "item {:?} has no priority", c),
+            PriorityError::DuplicateItem(c) => {
+                write!(f, "item {:?} is given a priority more than once", c)
+            }
+            PriorityError::InvalidMapLine { line_number, line } => write!(
+                f,
+                "line {}: expected `<item> <priority>`, got {:?}",
+                line_number, line
+            ),
+            PriorityError::Io(msg) => write!(f, "could not read priority map: {}", msg),
+            PriorityError::MissingValue(option) => write!(f, "{} needs a value", option),
+        }
+    }
+}
+
+impl std::error::Error for PriorityError {}
+
+impl Default for PriorityTable {
+    fn default() -> Self {
+        Self::from_ordering(&('a'..='z').chain('A'..='Z').collect::<String>()).unwrap()
+    }
+}
+
+impl PriorityTable {
+    /// Each item's priority is its 1-based position in `items`.
+    pub fn from_ordering(items: &str) -> Result<Self, PriorityError> {
+        Self::from_pairs(items.chars().zip(1..))
+    }
+
+    pub fn from_pairs<I>(pairs: I) -> Result<Self, PriorityError>
+    where
+        I: IntoIterator<Item = (char, i64)>,
+    {
+        let mut priorities = HashMap::new();
+        for (item, priority) in pairs {
+            if priorities.insert(item, priority).is_some() {
+                return Err(PriorityError::DuplicateItem(item));
+            }
+        }
+        Ok(Self { priorities })
+    }
+
+    /// Reads a map file with one `<item> <priority>` pair per line. Blank lines and
+    /// lines starting with `#` are ignored.
+    pub fn from_map_file<PathType>(filename: PathType) -> Result<Self, PriorityError>
+    where
+        PathType: AsRef<Path>,
+    {
+        fs::read_to_string(filename)
+            .map_err(|err| PriorityError::Io(err.to_string()))?
+            .parse()
+    }
+
+    /// Builds a table from command line arguments: `--alphabet <items>` or
+    /// `--priority-map <file>`, falling back to the default AoC alphabet.
+    pub fn from_args(args: &[String]) -> Result<Self, PriorityError> {
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--alphabet" => {
+                    let items = args
+                        .next()
+                        .ok_or(PriorityError::MissingValue("--alphabet"))?;
+                    return Self::from_ordering(items);
+                }
+                "--priority-map" => {
+                    let file = args
+                        .next()
+                        .ok_or(PriorityError::MissingValue("--priority-map"))?;
+                    return Self::from_map_file(file);
+                }
+                _ => {}
+            }
+        }
+        Ok(Self::default())
+    }
+
+    pub fn priority(&self, item: char) -> Result<i64, PriorityError> {
+        self.priorities
+            .get(&item)
+            .copied()
+            .ok_or(PriorityError::UnknownItem(item))
+    }
+
+    /// Checks that every item in `items` has a priority.
+    pub fn validate(&self, items: &str) -> Result<(), PriorityError> {
+        items
+            .chars()
+            .try_for_each(|item| self.priority(item).map(|_| ()))
+    }
+}
+
+impl FromStr for PriorityTable {
+    type Err = PriorityError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut pairs = Vec::new();
+        for (idx, line) in s.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let invalid = || PriorityError::InvalidMapLine {
+                line_number: idx + 1,
+                line: line.into(),
+            };
+            let mut parts = trimmed.split_whitespace();
+            let mut item_chars = parts.next().ok_or_else(invalid)?.chars();
+            let item = item_chars.next().ok_or_else(invalid)?;
+            let priority = parts
+                .next()
+                .and_then(|p| p.parse::<i64>().ok())
+                .ok_or_else(invalid)?;
+            if item_chars.next().is_some() || parts.next().is_some() {
+                return Err(invalid());
+            }
+            pairs.push((item, priority));
+        }
+        Self::from_pairs(pairs)
+    }
+}
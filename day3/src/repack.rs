@@ -0,0 +1,259 @@
+use std::fmt;
+
+use crate::{Group, Rucksack};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub rucksack: usize,
+    pub compartment: usize,
+}
+
+/// Moves every copy of `item` found at `from` over to `to`.
+#[derive(Debug, Clone)]
+pub struct Move {
+    pub item: char,
+    pub count: usize,
+    pub from: Location,
+    pub to: Location,
+}
+
+#[derive(Debug)]
+pub struct RepackPlan {
+    pub moves: Vec<Move>,
+    pub rucksacks: Vec<Rucksack>,
+    /// Groups that moving items between their own rucksacks cannot leave with exactly one
+    /// badge: those sharing no item at all, and a trailing group of a single rucksack
+    /// holding more than one item type.
+    pub unresolved_groups: Vec<usize>,
+    /// Rucksacks whose compartments no longer hold the same number of items as before,
+    /// since items are moved rather than swapped.
+    pub unbalanced_rucksacks: Vec<usize>,
+}
+
+impl RepackPlan {
+    pub fn item_moves(&self) -> usize {
+        self.moves.iter().map(|m| m.count).sum()
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "rucksack {} compartment {}",
+            self.rucksack,
+            self.compartment + 1
+        )
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {} x {:?} from {} to {}",
+            self.count, self.item, self.from, self.to
+        )
+    }
+}
+
+fn count_in(compartment: &str, item: char) -> usize {
+    compartment.chars().filter(|&c| c == item).count()
+}
+
+// moves every copy of the item out of one compartment and into another, recording the move
+fn transfer(rucksacks: &mut [Rucksack], item: char, from: Location, to: Location) -> Move {
+    let source = &mut rucksacks[from.rucksack].compartments[from.compartment];
+    let count = count_in(source, item);
+    source.retain(|c| c != item);
+    rucksacks[to.rucksack].compartments[to.compartment].extend(std::iter::repeat_n(item, count));
+    Move {
+        item,
+        count,
+        from,
+        to,
+    }
+}
+
+// leaves exactly one badge in the group by emptying one rucksack of each extra shared item
+fn repack_group(rucksacks: &mut [Rucksack], first: usize, moves: &mut Vec<Move>) -> bool {
+    let badges = Group {
+        rucksacks: &rucksacks[first..],
+    }
+    .badges();
+    let members = first..rucksacks.len();
+    if badges.is_empty() || (members.len() < 2 && badges.len() > 1) {
+        return false;
+    }
+
+    let members = (first..rucksacks.len()).collect::<Vec<_>>();
+    let count =
+        |rucksacks: &[Rucksack], idx: usize, item: char| count_in(&rucksacks[idx].items(), item);
+    // the cheapest badge to get rid of is the one with the fewest copies in some rucksack
+    let removal_cost = |rucksacks: &[Rucksack], item: char| {
+        members
+            .iter()
+            .map(|&idx| count(rucksacks, idx, item))
+            .min()
+            .unwrap()
+    };
+    let keep = *badges
+        .iter()
+        .rev()
+        .max_by_key(|&&item| removal_cost(rucksacks, item))
+        .unwrap();
+
+    for &item in badges.iter().filter(|&&item| item != keep) {
+        let source = *members
+            .iter()
+            .min_by_key(|&&idx| count(rucksacks, idx, item))
+            .unwrap();
+        let dest = *members
+            .iter()
+            .filter(|&&idx| idx != source)
+            .max_by_key(|&&idx| count(rucksacks, idx, item))
+            .unwrap();
+        // put the items next to the existing copies, so no new compartment overlap appears
+        let dest_compartment = (0..2)
+            .find(|&c| rucksacks[dest].compartments[c].contains(item))
+            .unwrap();
+        for compartment in 0..2 {
+            if rucksacks[source].compartments[compartment].contains(item) {
+                moves.push(transfer(
+                    rucksacks,
+                    item,
+                    Location {
+                        rucksack: source,
+                        compartment,
+                    },
+                    Location {
+                        rucksack: dest,
+                        compartment: dest_compartment,
+                    },
+                ));
+            }
+        }
+    }
+    true
+}
+
+// moves the smaller side of every item found in both compartments, breaking ties towards
+// the emptier compartment
+fn repack_compartments(rucksacks: &mut [Rucksack], idx: usize, moves: &mut Vec<Move>) {
+    for item in rucksacks[idx].in_both_compartments() {
+        let compartments = &rucksacks[idx].compartments;
+        let counts = [
+            count_in(&compartments[0], item),
+            count_in(&compartments[1], item),
+        ];
+        let from = if counts[0] != counts[1] {
+            if counts[0] < counts[1] {
+                0
+            } else {
+                1
+            }
+        } else if compartments[0].chars().count() > compartments[1].chars().count() {
+            0
+        } else {
+            1
+        };
+        moves.push(transfer(
+            rucksacks,
+            item,
+            Location {
+                rucksack: idx,
+                compartment: from,
+            },
+            Location {
+                rucksack: idx,
+                compartment: 1 - from,
+            },
+        ));
+    }
+}
+
+/// Proposes item moves so no rucksack has an item type in both compartments, and every
+/// group of `group_size` rucksacks shares exactly one badge.
+///
+/// Extra badges are removed first, by moving all copies of the item out of the rucksack
+/// holding the fewest into another rucksack of the group. Compartment overlaps are then
+/// removed by moving the smaller side across. Each step picks the cheapest move for each
+/// item type on its own, so the total number of items moved is not necessarily minimal.
+/// Moves change compartment sizes; rucksacks left with different sizes are reported in
+/// `unbalanced_rucksacks`.
+///
+/// Panics if `group_size` is 0.
+pub fn plan(rucksacks: &[Rucksack], group_size: usize) -> RepackPlan {
+    assert!(group_size > 0, "group size must be at least 1");
+    let mut repacked = rucksacks.to_vec();
+    let mut moves = Vec::new();
+    let mut unresolved_groups = Vec::new();
+
+    for (group_idx, first) in (0..repacked.len()).step_by(group_size).enumerate() {
+        let last = usize::min(first + group_size, repacked.len());
+        if !repack_group(&mut repacked[..last], first, &mut moves) {
+            unresolved_groups.push(group_idx);
+        }
+    }
+
+    for idx in 0..repacked.len() {
+        repack_compartments(&mut repacked, idx, &mut moves);
+    }
+
+    let sizes = |rucksack: &Rucksack| rucksack.compartments.clone().map(|c| c.chars().count());
+    let unbalanced_rucksacks = (0..repacked.len())
+        .filter(|&idx| sizes(&repacked[idx]) != sizes(&rucksacks[idx]))
+        .collect();
+
+    RepackPlan {
+        moves,
+        rucksacks: repacked,
+        unresolved_groups,
+        unbalanced_rucksacks,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rucksack(items: &str) -> Rucksack {
+        let (left, right) = items.split_at(items.len() / 2);
+        Rucksack {
+            compartments: [left.into(), right.into()],
+        }
+    }
+
+    #[test]
+    fn trailing_partial_group() {
+        let rucksacks = ["abcB", "aBde", "aBfg", "xyzw"].map(rucksack);
+        let plan = plan(&rucksacks, 3);
+        assert_eq!(plan.unresolved_groups, vec![1]);
+        let badges = Group {
+            rucksacks: &plan.rucksacks[..3],
+        }
+        .badges();
+        assert_eq!(badges.len(), 1);
+    }
+
+    #[test]
+    fn groups_of_one() {
+        let rucksacks = ["aa", "ab", "abcd"].map(rucksack);
+        let plan = plan(&rucksacks, 1);
+        assert_eq!(plan.unresolved_groups, vec![1, 2]);
+        assert!(plan
+            .rucksacks
+            .iter()
+            .all(|r| r.in_both_compartments().is_empty()));
+    }
+
+    #[test]
+    fn reports_resized_compartments() {
+        let rucksacks = ["aabcad", "xyzuvw"].map(rucksack);
+        let plan = plan(&rucksacks, 1);
+        assert_eq!(plan.unbalanced_rucksacks, vec![0]);
+        let [left, right] = &plan.rucksacks[0].compartments;
+        assert_eq!(left.chars().count() + right.chars().count(), 6);
+        assert_ne!(left.chars().count(), right.chars().count());
+    }
+}
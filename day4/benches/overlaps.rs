@@ -0,0 +1,66 @@
+use std::{env, hint::black_box, time::Instant};
+
+use common::{interval::Interval, rng::Rng};
+use day4::index::{naive_overlapping_pairs, IntervalIndex};
+
+// short assignments spread over a long row of sections, so the number of overlapping
+// pairs stays close to linear in the number of assignments
+fn generate_assignments(rng: &mut Rng, count: usize) -> Vec<(Interval<i64>, usize)> {
+    let span = count as u64 * 100;
+    (0..count)
+        .map(|id| (generate_interval(rng, span), id))
+        .collect()
+}
+
+fn generate_interval(rng: &mut Rng, span: u64) -> Interval<i64> {
+    let start = rng.range_inclusive(1, span) as i64;
+    let len = rng.range_inclusive(1, 1000) as i64;
+    Interval::new(start, start + len - 1)
+}
+
+fn time<T, F: FnOnce() -> T>(name: &str, f: F) -> T {
+    let start = Instant::now();
+    let res = black_box(f());
+    println!("{:<36} {:>12?}", name, start.elapsed());
+    res
+}
+
+fn main() {
+    let count = env::args()
+        .skip(1)
+        .find_map(|arg| arg.parse::<usize>().ok())
+        .unwrap_or(200_000);
+    let mut rng = Rng::new(4);
+
+    // all-pairs is quadratic, so compare against it on a smaller list
+    for count in [count / 10, count] {
+        println!("{} assignments", count);
+        let assignments = generate_assignments(&mut rng, count);
+        let index = time("build index", || IntervalIndex::new(assignments.clone()));
+        let indexed = time("overlapping pairs, index sweep", || {
+            index.overlapping_pairs().len()
+        });
+        println!("{} overlapping pairs", indexed);
+        if count <= 50_000 {
+            let naive = time("overlapping pairs, naive all-pairs", || {
+                naive_overlapping_pairs(&assignments).len()
+            });
+            assert_eq!(indexed, naive);
+        }
+
+        // queries land anywhere along the same row as the assignments
+        let queries = (0..10_000)
+            .map(|_| generate_interval(&mut rng, count as u64 * 100))
+            .collect::<Vec<_>>();
+        let indexed = time("10000 range queries, index", || {
+            queries.iter().map(|q| index.query(q).len()).sum::<usize>()
+        });
+        let naive = time("10000 range queries, linear scan", || {
+            queries
+                .iter()
+                .map(|q| assignments.iter().filter(|(a, _)| a.overlaps(q)).count())
+                .sum::<usize>()
+        });
+        assert_eq!(indexed, naive);
+    }
+}
@@ -0,0 +1,99 @@
+use common::interval::Interval;
+
+use crate::index::{bounds, IntervalIndex};
+
+/// A freed assignment and the kept assignments taking over each part of it.
+#[derive(Debug)]
+pub struct Reassignment<'a, Id> {
+    pub freed: &'a (Interval<i64>, Id),
+    pub taken_over_by: Vec<(Interval<i64>, &'a Id)>,
+}
+
+#[derive(Debug)]
+pub struct CoverPlan<'a, Id> {
+    pub kept: Vec<&'a (Interval<i64>, Id)>,
+    pub reassignments: Vec<Reassignment<'a, Id>>,
+}
+
+// Greedy interval cover: from the first section not yet covered, keep whichever assignment
+// starting at or before it reaches furthest. This is optimal, since any cover has to
+// include some assignment covering that section, and none of them reaches further.
+fn greedy_cover<Id>(entries: &[(Interval<i64>, Id)]) -> Vec<usize> {
+    let mut order = (0..entries.len())
+        .filter(|&idx| !entries[idx].0.is_empty())
+        .collect::<Vec<_>>();
+    order.sort_by_key(|&idx| bounds(&entries[idx].0));
+
+    let mut kept = Vec::new();
+    let mut covered_to: Option<i64> = None;
+    let mut next = 0;
+    while next < order.len() {
+        let start = bounds(&entries[order[next]].0).0;
+        // the first section that still needs covering, skipping over gaps nobody is assigned
+        let point = match covered_to {
+            Some(covered) if start <= covered.saturating_add(1) => covered + 1,
+            _ => start,
+        };
+        let mut best: Option<(usize, i64)> = None;
+        while next < order.len() && bounds(&entries[order[next]].0).0 <= point {
+            let end = bounds(&entries[order[next]].0).1;
+            if best.is_none_or(|(_, best_end)| end > best_end) {
+                best = Some((order[next], end));
+            }
+            next += 1;
+        }
+        if let Some((idx, end)) = best {
+            if covered_to.is_none_or(|covered| end > covered) {
+                kept.push(idx);
+                covered_to = Some(end);
+                if end == i64::MAX {
+                    break;
+                }
+            }
+        }
+    }
+    kept
+}
+
+/// Finds a smallest set of assignments whose union still covers every assigned section,
+/// and splits every other assignment between the kept ones.
+pub fn plan<Id>(entries: &[(Interval<i64>, Id)]) -> CoverPlan<'_, Id> {
+    let kept_indices = greedy_cover(entries);
+    let mut is_kept = vec![false; entries.len()];
+    for &idx in &kept_indices {
+        is_kept[idx] = true;
+    }
+    let kept_index = IntervalIndex::new(kept_indices.iter().map(|&idx| (entries[idx].0, idx)));
+
+    let reassignments = entries
+        .iter()
+        .enumerate()
+        .filter(|(idx, (interval, _))| !is_kept[*idx] && !interval.is_empty())
+        .map(|(_, freed)| {
+            // the kept assignments are disjoint except where they chain, so hand each
+            // section to the first kept assignment that covers it
+            let mut taken_over_by = Vec::new();
+            let mut remaining = freed.0;
+            for (kept, kept_idx) in kept_index.query(&freed.0) {
+                let part = remaining.intersection(kept);
+                if part.is_empty() {
+                    continue;
+                }
+                taken_over_by.push((part, &entries[*kept_idx].1));
+                remaining = remaining
+                    .difference(&part)
+                    .pop()
+                    .unwrap_or(Interval::empty());
+            }
+            Reassignment {
+                freed,
+                taken_over_by,
+            }
+        })
+        .collect();
+
+    CoverPlan {
+        kept: kept_indices.iter().map(|&idx| &entries[idx]).collect(),
+        reassignments,
+    }
+}
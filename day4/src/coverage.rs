@@ -0,0 +1,154 @@
+use std::{collections::BTreeMap, fmt};
+
+use common::interval::{Interval, IntervalSet};
+
+/// A run of consecutive sections assigned to the same number of elves.
+#[derive(Debug, Clone, Copy)]
+pub struct Segment {
+    pub sections: Interval<i64>,
+    pub depth: usize,
+}
+
+#[derive(Debug)]
+pub struct Coverage {
+    pub assignments: usize,
+    /// Sections assigned to at least one elf, in order.
+    pub segments: Vec<Segment>,
+    pub covered: IntervalSet<i64>,
+    /// Sum of the lengths of all assignments.
+    pub assigned_len: u64,
+}
+
+/// Sweeps over the start and end of every assignment, tracking how many elves are
+/// assigned to each run of sections.
+pub fn sweep<'a, I>(ranges: I) -> Coverage
+where
+    I: IntoIterator<Item = &'a Interval<i64>>,
+{
+    let mut deltas = BTreeMap::<i64, isize>::new();
+    let mut assignments = 0;
+    let mut assigned_len = 0u64;
+    for range in ranges {
+        assignments += 1;
+        let (Some(start), Some(end)) = (range.start(), range.end()) else {
+            continue;
+        };
+        assigned_len = assigned_len.saturating_add(range.len());
+        *deltas.entry(start).or_default() += 1;
+        // a range running to the very last section never ends
+        if let Some(after_end) = end.checked_add(1) {
+            *deltas.entry(after_end).or_default() -= 1;
+        }
+    }
+
+    let mut segments = Vec::new();
+    let mut depth = 0isize;
+    let mut events = deltas.into_iter().peekable();
+    while let Some((position, delta)) = events.next() {
+        depth += delta;
+        if depth == 0 {
+            continue;
+        }
+        let end = events.peek().map_or(i64::MAX, |(next, _)| next - 1);
+        segments.push(Segment {
+            sections: Interval::new(position, end),
+            depth: depth as usize,
+        });
+    }
+
+    let covered = segments.iter().map(|segment| segment.sections).collect();
+    Coverage {
+        assignments,
+        segments,
+        covered,
+        assigned_len,
+    }
+}
+
+impl Coverage {
+    /// Sections between the first and last assigned section that no elf is assigned to.
+    pub fn uncovered(&self) -> Vec<Interval<i64>> {
+        self.covered.gaps()
+    }
+
+    /// Merged runs of sections whose number of assigned elves matches `pred`.
+    pub fn with_depth<P>(&self, pred: P) -> IntervalSet<i64>
+    where
+        P: Fn(usize) -> bool,
+    {
+        self.segments
+            .iter()
+            .filter(|segment| pred(segment.depth))
+            .map(|segment| segment.sections)
+            .collect()
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.segments
+            .iter()
+            .map(|segment| segment.depth)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Section assignments beyond the first for each section, i.e. work that would be
+    /// done more than once.
+    pub fn redundant_len(&self) -> u64 {
+        self.assigned_len - self.covered.len()
+    }
+}
+
+fn format_intervals(intervals: &[Interval<i64>]) -> String {
+    if intervals.is_empty() {
+        return "none".into();
+    }
+    intervals
+        .iter()
+        .map(|interval| interval.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let single = self.with_depth(|depth| depth == 1);
+        let many = self.with_depth(|depth| depth > 1);
+        let max_depth = self.max_depth();
+        let deepest = self.with_depth(|depth| depth == max_depth);
+        let uncovered = self.uncovered();
+
+        writeln!(f, "assignments {}", self.assignments)?;
+        writeln!(f, "span {}", self.covered.hull())?;
+        writeln!(f, "sections covered {}", self.covered.len())?;
+        writeln!(
+            f,
+            "sections uncovered {}: {}",
+            uncovered.iter().map(Interval::len).sum::<u64>(),
+            format_intervals(&uncovered)
+        )?;
+        writeln!(
+            f,
+            "sections covered by exactly one elf {}: {}",
+            single.len(),
+            format_intervals(single.intervals())
+        )?;
+        writeln!(
+            f,
+            "sections covered by more than one elf {}: {}",
+            many.len(),
+            format_intervals(many.intervals())
+        )?;
+        writeln!(
+            f,
+            "max overlap depth {}: {}",
+            max_depth,
+            format_intervals(deepest.intervals())
+        )?;
+        writeln!(
+            f,
+            "total assigned {} redundant {}",
+            self.assigned_len,
+            self.redundant_len()
+        )
+    }
+}
@@ -0,0 +1,124 @@
+use common::interval::Interval;
+
+/// A static interval tree over section assignments.
+///
+/// Entries are sorted by start and viewed as an implicit balanced binary tree (the root
+/// is the middle entry, each half is a subtree), with every node remembering the largest
+/// end in its subtree so whole subtrees can be skipped during queries.
+#[derive(Debug)]
+pub struct IntervalIndex<Id> {
+    entries: Vec<(Interval<i64>, Id)>,
+    max_end: Vec<i64>,
+}
+
+// bounds of a non-empty interval, which is all the index ever stores
+pub(crate) fn bounds(interval: &Interval<i64>) -> (i64, i64) {
+    (interval.start().unwrap(), interval.end().unwrap())
+}
+
+impl<Id> IntervalIndex<Id> {
+    /// Builds the index, leaving out empty intervals since they overlap nothing.
+    pub fn new<I>(entries: I) -> Self
+    where
+        I: IntoIterator<Item = (Interval<i64>, Id)>,
+    {
+        let mut entries = entries
+            .into_iter()
+            .filter(|(interval, _)| !interval.is_empty())
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|(interval, _)| bounds(interval));
+        let mut index = Self {
+            max_end: vec![i64::MIN; entries.len()],
+            entries,
+        };
+        index.fill_max_end(0, index.entries.len());
+        index
+    }
+
+    fn fill_max_end(&mut self, low: usize, high: usize) -> i64 {
+        if low >= high {
+            return i64::MIN;
+        }
+        let mid = low + (high - low) / 2;
+        let left = self.fill_max_end(low, mid);
+        let right = self.fill_max_end(mid + 1, high);
+        let max_end = bounds(&self.entries[mid].0).1.max(left).max(right);
+        self.max_end[mid] = max_end;
+        max_end
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Every entry sharing at least one section with `range`, in order of start.
+    pub fn query(&self, range: &Interval<i64>) -> Vec<&(Interval<i64>, Id)> {
+        let mut found = Vec::new();
+        if let (Some(start), Some(end)) = (range.start(), range.end()) {
+            self.query_into(0, self.entries.len(), start, end, &mut found);
+        }
+        found
+    }
+
+    fn query_into<'a>(
+        &'a self,
+        low: usize,
+        high: usize,
+        start: i64,
+        end: i64,
+        found: &mut Vec<&'a (Interval<i64>, Id)>,
+    ) {
+        if low >= high {
+            return;
+        }
+        let mid = low + (high - low) / 2;
+        // nothing in this subtree reaches the query
+        if self.max_end[mid] < start {
+            return;
+        }
+        self.query_into(low, mid, start, end, found);
+        let (entry_start, entry_end) = bounds(&self.entries[mid].0);
+        // everything from here on starts after the query ends
+        if entry_start > end {
+            return;
+        }
+        if entry_end >= start {
+            found.push(&self.entries[mid]);
+        }
+        self.query_into(mid + 1, high, start, end, found);
+    }
+
+    /// Every pair of overlapping entries, found by sweeping over the entries in order of
+    /// start: an entry overlaps exactly the following entries that start before it ends.
+    /// Runs in `O(n + k)` for `k` pairs once the index is built.
+    pub fn overlapping_pairs(&self) -> Vec<(&Id, &Id)> {
+        let mut pairs = Vec::new();
+        for (idx, (interval, id)) in self.entries.iter().enumerate() {
+            let end = bounds(interval).1;
+            for (_, other_id) in self.entries[idx + 1..]
+                .iter()
+                .take_while(|(other, _)| bounds(other).0 <= end)
+            {
+                pairs.push((id, other_id));
+            }
+        }
+        pairs
+    }
+}
+
+/// Reference implementation of [`IntervalIndex::overlapping_pairs`], checking every pair.
+pub fn naive_overlapping_pairs<Id>(entries: &[(Interval<i64>, Id)]) -> Vec<(&Id, &Id)> {
+    let mut pairs = Vec::new();
+    for (idx, (interval, id)) in entries.iter().enumerate() {
+        for (other, other_id) in &entries[idx + 1..] {
+            if interval.overlaps(other) {
+                pairs.push((id, other_id));
+            }
+        }
+    }
+    pairs
+}
@@ -1,9 +1,14 @@
-use common::{Parsable, ParseStatus};
+use std::{env, process};
+
+use common::{interval::Interval, Parsable, ParseStatus};
+use day4::{cover, index::IntervalIndex};
+
+mod coverage;
 
 #[derive(Debug)]
 struct CleanupPair {
-    range_elf1: (i64, i64),
-    range_elf2: (i64, i64),
+    range_elf1: Interval<i64>,
+    range_elf2: Interval<i64>,
 }
 
 impl Parsable for CleanupPair {
@@ -13,8 +18,8 @@ impl Parsable for CleanupPair {
             .map(|s| s.parse::<i64>().unwrap())
             .collect::<Vec<_>>();
         curr_item.replace(CleanupPair {
-            range_elf1: (indices[0], indices[1]),
-            range_elf2: (indices[2], indices[3]),
+            range_elf1: Interval::new(indices[0], indices[1]),
+            range_elf2: Interval::new(indices[2], indices[3]),
         });
         return ParseStatus::ItemComplete;
     }
@@ -22,26 +27,13 @@ impl Parsable for CleanupPair {
 
 impl CleanupPair {
     fn fully_contains(&self) -> bool {
-        // check if elf 1's assigned range is fully contained in elf 2's
-        if self.range_elf2.0 <= self.range_elf1.0 && self.range_elf2.1 >= self.range_elf1.1 {
-            return true;
-        }
-
-        // check if elf 2's assigned range is fully contained in elf 1's
-        if self.range_elf1.0 <= self.range_elf2.0 && self.range_elf1.1 >= self.range_elf2.1 {
-            return true;
-        }
-
-        return false;
+        // check if either elf's assigned range is fully contained in the other's
+        self.range_elf2.contains_interval(&self.range_elf1)
+            || self.range_elf1.contains_interval(&self.range_elf2)
     }
 
     fn overlap(&self) -> bool {
-        // check if one elf's range is copmletely after the other's
-        if self.range_elf1.0 > self.range_elf2.1 || self.range_elf2.0 > self.range_elf1.1 {
-            return false;
-        }
-
-        return true;
+        self.range_elf1.overlaps(&self.range_elf2)
     }
 }
 
@@ -56,4 +48,79 @@ fn main() {
         "groups overlap {:?}",
         pairs.iter().filter(|&pair| pair.overlap()).count()
     );
+
+    if env::args().any(|arg| arg == "--coverage") {
+        let ranges = pairs
+            .iter()
+            .flat_map(|pair| [&pair.range_elf1, &pair.range_elf2]);
+        print!("{}", coverage::sweep(ranges));
+    }
+
+    // elves are identified by their pair and position in it, both 1-based
+    let elves = pairs
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, pair)| {
+            [
+                (pair.range_elf1, (idx + 1, 1)),
+                (pair.range_elf2, (idx + 1, 2)),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let index = IntervalIndex::new(elves.iter().copied());
+    let args = env::args().collect::<Vec<_>>();
+    if let Some(position) = args.iter().position(|arg| arg == "--overlapping") {
+        let usage = || -> ! {
+            eprintln!("usage: day4 --overlapping <start>-<end>");
+            process::exit(1);
+        };
+        let (start, end) = args
+            .get(position + 1)
+            .and_then(|bounds| bounds.split_once('-'))
+            .unwrap_or_else(|| usage());
+        let range = match (start.parse::<i64>(), end.parse::<i64>()) {
+            (Ok(start), Ok(end)) => Interval::new(start, end),
+            _ => usage(),
+        };
+        let found = index.query(&range);
+        println!("elves overlapping {} {}", range, found.len());
+        for (interval, (pair, elf)) in found {
+            println!("pair {} elf {}: {}", pair, elf, interval);
+        }
+    }
+    if args.iter().any(|arg| arg == "--overlapping-pairs") {
+        let overlapping = index.overlapping_pairs();
+        println!("overlapping elf pairs {}", overlapping.len());
+        for ((pair, elf), (other_pair, other_elf)) in overlapping {
+            println!(
+                "pair {} elf {} overlaps pair {} elf {}",
+                pair, elf, other_pair, other_elf
+            );
+        }
+    }
+
+    if args.iter().any(|arg| arg == "--optimise") {
+        let plan = cover::plan(&elves);
+        println!(
+            "elves {} needed {} freed {}",
+            elves.len(),
+            plan.kept.len(),
+            plan.reassignments.len()
+        );
+        for reassignment in &plan.reassignments {
+            let (interval, (pair, elf)) = reassignment.freed;
+            let taken_over_by = reassignment
+                .taken_over_by
+                .iter()
+                .map(|(part, (pair, elf))| format!("{} by pair {} elf {}", part, pair, elf))
+                .collect::<Vec<_>>();
+            println!(
+                "free pair {} elf {} ({}): {}",
+                pair,
+                elf,
+                interval,
+                taken_over_by.join(", ")
+            );
+        }
+    }
 }
@@ -0,0 +1,140 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    crane::{Crane, InstructionError},
+    CargoLoad, Instruction,
+};
+
+/// The drawing of the load after an instruction, starting with the initial load.
+pub struct Frame {
+    pub caption: String,
+    pub drawing: String,
+}
+
+/// Applies the instructions one at a time, recording a frame after each. Fails with the
+/// index of the first instruction that cannot be carried out.
+pub fn frames(
+    load: &CargoLoad,
+    instructions: &[Instruction],
+    crane: &dyn Crane,
+) -> Result<Vec<Frame>, (usize, InstructionError)> {
+    let mut load = load.clone();
+    let mut frames = vec![Frame {
+        caption: format!("{}: initial arrangement", crane.name()),
+        drawing: load.to_string(),
+    }];
+    for (idx, instruction) in instructions.iter().enumerate() {
+        load.apply_instruction(instruction, crane)
+            .map_err(|err| (idx, err))?;
+        frames.push(Frame {
+            caption: format!(
+                "{}: step {}/{}: move {} from {} to {}",
+                crane.name(),
+                idx + 1,
+                instructions.len(),
+                instruction.count,
+                instruction.source_stack + 1,
+                instruction.dest_stack + 1
+            ),
+            drawing: load.to_string(),
+        });
+    }
+    Ok(frames)
+}
+
+/// Redraws the whole terminal for every frame.
+pub fn play_terminal(frames: &[Frame], delay: Duration) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    for frame in frames {
+        // clear the screen and move the cursor to the top left
+        write!(
+            stdout,
+            "\x1b[2J\x1b[H{}\n\n{}\n",
+            frame.caption, frame.drawing
+        )?;
+        stdout.flush()?;
+        thread::sleep(delay);
+    }
+    Ok(())
+}
+
+/// Writes every frame to its own numbered text file in `dir`.
+pub fn write_text_frames<PathType>(frames: &[Frame], dir: PathType) -> io::Result<()>
+where
+    PathType: AsRef<Path>,
+{
+    fs::create_dir_all(&dir)?;
+    for (idx, frame) in frames.iter().enumerate() {
+        let path = dir.as_ref().join(format!("frame_{:05}.txt", idx));
+        fs::write(path, format!("{}\n\n{}\n", frame.caption, frame.drawing))?;
+    }
+    Ok(())
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Writes an animated SVG showing each frame for `delay`, looping forever.
+pub fn write_svg<PathType>(frames: &[Frame], path: PathType, delay: Duration) -> io::Result<()>
+where
+    PathType: AsRef<Path>,
+{
+    const CHAR_WIDTH: usize = 10;
+    const LINE_HEIGHT: usize = 18;
+
+    let lines = frames
+        .iter()
+        .map(|frame| {
+            let mut lines = vec![frame.caption.as_str(), ""];
+            lines.extend(frame.drawing.lines());
+            lines
+        })
+        .collect::<Vec<_>>();
+    let width = lines
+        .iter()
+        .flatten()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    let height = lines.iter().map(Vec::len).max().unwrap_or(0);
+    let total = delay.as_secs_f64() * frames.len() as f64;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+         font-family=\"monospace\" font-size=\"16\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n",
+        (width + 2) * CHAR_WIDTH,
+        (height + 1) * LINE_HEIGHT
+    );
+    for (idx, frame_lines) in lines.iter().enumerate() {
+        // each frame is only visible during its own slice of the loop
+        let shown = idx as f64 / frames.len() as f64;
+        let hidden = (idx + 1) as f64 / frames.len() as f64;
+        svg += &format!(
+            "<g visibility=\"hidden\">\n<animate attributeName=\"visibility\" \
+             values=\"hidden;visible;hidden\" keyTimes=\"0;{:.6};{:.6}\" dur=\"{:.3}s\" \
+             calcMode=\"discrete\" repeatCount=\"indefinite\"/>\n",
+            shown, hidden, total
+        );
+        for (line_idx, line) in frame_lines.iter().enumerate() {
+            svg += &format!(
+                "<text x=\"{}\" y=\"{}\" xml:space=\"preserve\">{}</text>\n",
+                CHAR_WIDTH,
+                (line_idx + 1) * LINE_HEIGHT,
+                escape_xml(line)
+            );
+        }
+        svg += "</g>\n";
+    }
+    svg += "</svg>\n";
+    fs::write(path, svg)
+}
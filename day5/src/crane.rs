@@ -0,0 +1,109 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum InstructionError {
+    NoSuchStack {
+        stack: usize,
+    },
+    NotEnoughCrates {
+        stack: usize,
+        requested: usize,
+        available: usize,
+    },
+}
+
+impl fmt::Display for InstructionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // stacks are reported with their 1-based number, as in the drawing
+        match self {
+            InstructionError::NoSuchStack { stack } => write!(f, "there is no stack {}", stack + 1),
+            InstructionError::NotEnoughCrates {
+                stack,
+                requested,
+                available,
+            } => write!(
+                f,
+                "cannot move {} crates from stack {}, which only has {}",
+                requested,
+                stack + 1,
+                available
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InstructionError {}
+
+/// How a crane carries out a move of several crates.
+///
+/// A move is made of lifts: each lift takes some crates off the top of the source stack
+/// and puts them on the destination stack keeping their order.
+pub trait Crane {
+    fn name(&self) -> String;
+    /// The number of crates carried by each lift, in order, summing to `count`.
+    fn lifts(&self, count: usize) -> Vec<usize>;
+}
+
+/// Moves one crate at a time, so a move reverses the crates it carries.
+pub struct CrateMover9000;
+
+/// Moves all crates at once, so a move keeps their order.
+pub struct CrateMover9001;
+
+/// Carries at most `capacity` crates per lift, so larger moves are split into batches.
+pub struct LimitedCrane {
+    pub capacity: usize,
+}
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> String {
+        "CrateMover 9000".into()
+    }
+
+    fn lifts(&self, count: usize) -> Vec<usize> {
+        vec![1; count]
+    }
+}
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> String {
+        "CrateMover 9001".into()
+    }
+
+    fn lifts(&self, count: usize) -> Vec<usize> {
+        if count == 0 {
+            return vec![];
+        }
+        vec![count]
+    }
+}
+
+impl Crane for LimitedCrane {
+    fn name(&self) -> String {
+        format!("crane lifting up to {} crates", self.capacity)
+    }
+
+    fn lifts(&self, count: usize) -> Vec<usize> {
+        assert!(self.capacity > 0, "a crane has to lift at least one crate");
+        let mut lifts = vec![self.capacity; count / self.capacity];
+        if !count.is_multiple_of(self.capacity) {
+            lifts.push(count % self.capacity);
+        }
+        lifts
+    }
+}
+
+/// Picks a crane by name: `9000`, `9001` or `limited:<capacity>`.
+pub fn from_name(name: &str) -> Option<Box<dyn Crane>> {
+    match name {
+        "9000" => Some(Box::new(CrateMover9000)),
+        "9001" => Some(Box::new(CrateMover9001)),
+        _ => {
+            let capacity = name.strip_prefix("limited:")?.parse().ok()?;
+            if capacity == 0 {
+                return None;
+            }
+            Some(Box::new(LimitedCrane { capacity }))
+        }
+    }
+}
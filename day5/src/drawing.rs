@@ -0,0 +1,287 @@
+use std::{fmt, str::FromStr};
+
+use crate::{CargoLoad, CrateStack};
+
+#[derive(Debug, PartialEq)]
+pub enum DrawingError {
+    Empty,
+    InvalidLabel { label: String },
+    UnexpectedLabel { expected: usize, found: usize },
+    InvalidCrate { line: usize, column: usize },
+    UnlabelledCrate { line: usize, column: usize },
+    DuplicateCrate { line: usize, column: usize },
+}
+
+impl fmt::Display for DrawingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DrawingError::Empty => write!(f, "the drawing has no stack labels"),
+            DrawingError::InvalidLabel { label } => {
+                write!(f, "stack label {:?} is not a number", label)
+            }
+            DrawingError::UnexpectedLabel { expected, found } => write!(
+                f,
+                "stacks must be numbered from 1 in order, expected {} but found {}",
+                expected, found
+            ),
+            DrawingError::InvalidCrate { line, column } => write!(
+                f,
+                "line {} column {}: expected a crate like `[A]`",
+                line, column
+            ),
+            DrawingError::UnlabelledCrate { line, column } => write!(
+                f,
+                "line {} column {}: crate is not above a stack label",
+                line, column
+            ),
+            DrawingError::DuplicateCrate { line, column } => write!(
+                f,
+                "line {} column {}: more than one crate on the same stack",
+                line, column
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DrawingError {}
+
+// character columns spanned by each label of the footer line, in order
+fn parse_footer(footer: &str) -> Result<Vec<(usize, usize)>, DrawingError> {
+    let chars = footer.chars().collect::<Vec<_>>();
+    let mut spans = Vec::new();
+    let mut column = 0;
+    while column < chars.len() {
+        if chars[column].is_whitespace() {
+            column += 1;
+            continue;
+        }
+        let start = column;
+        while column < chars.len() && !chars[column].is_whitespace() {
+            column += 1;
+        }
+        let label = chars[start..column].iter().collect::<String>();
+        let number = label
+            .parse::<usize>()
+            .map_err(|_| DrawingError::InvalidLabel {
+                label: label.clone(),
+            })?;
+        if number != spans.len() + 1 {
+            return Err(DrawingError::UnexpectedLabel {
+                expected: spans.len() + 1,
+                found: number,
+            });
+        }
+        spans.push((start, column - 1));
+    }
+    if spans.is_empty() {
+        return Err(DrawingError::Empty);
+    }
+    Ok(spans)
+}
+
+/// Parses the AoC drawing, top to bottom, ending with the line of stack numbers.
+///
+/// Stack numbers may have several digits; a crate `[X]` belongs to the stack whose
+/// number is written under any of its three columns. Lines may be of any length.
+impl FromStr for CargoLoad {
+    type Err = DrawingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // number the lines before dropping blank ones, so errors point at the right line
+        let lines = s
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .collect::<Vec<_>>();
+        let (footer, crate_lines) = lines.split_last().ok_or(DrawingError::Empty)?;
+        let labels = parse_footer(footer.1)?;
+
+        let mut stacks = Vec::<CrateStack>::new();
+        stacks.resize_with(labels.len(), Default::default);
+        // fill the stacks from the bottom up
+        for &(line_idx, line) in crate_lines.iter().rev() {
+            let line_number = line_idx + 1;
+            let chars = line.chars().collect::<Vec<_>>();
+            let mut filled = vec![false; stacks.len()];
+            let mut column = 0;
+            while column < chars.len() {
+                if chars[column].is_whitespace() {
+                    column += 1;
+                    continue;
+                }
+                let invalid = DrawingError::InvalidCrate {
+                    line: line_number,
+                    column: column + 1,
+                };
+                match chars.get(column..column + 3) {
+                    Some(&['[', contents, ']']) if !contents.is_whitespace() => {
+                        let stack_idx = labels
+                            .iter()
+                            .position(|&(start, end)| start <= column + 2 && column <= end)
+                            .ok_or(DrawingError::UnlabelledCrate {
+                                line: line_number,
+                                column: column + 1,
+                            })?;
+                        if filled[stack_idx] {
+                            return Err(DrawingError::DuplicateCrate {
+                                line: line_number,
+                                column: column + 1,
+                            });
+                        }
+                        filled[stack_idx] = true;
+                        stacks[stack_idx].crates.push_back(contents);
+                    }
+                    _ => return Err(invalid),
+                }
+                column += 3;
+            }
+        }
+        Ok(CargoLoad { stacks })
+    }
+}
+
+/// Renders the load as an AoC drawing, which parses back into the same load.
+///
+/// Every stack gets a column at least as wide as a crate, with its number under the
+/// middle of the crates; wider columns leave room for long stack numbers.
+impl fmt::Display for CargoLoad {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let labels = (1..=self.stacks.len())
+            .map(|label| label.to_string())
+            .collect::<Vec<_>>();
+        let widths = labels
+            .iter()
+            .map(|label| usize::max(3, label.len() + 1))
+            .collect::<Vec<_>>();
+        let height = self
+            .stacks
+            .iter()
+            .map(|stack| stack.crates.len())
+            .max()
+            .unwrap_or(0);
+
+        for level in (0..height).rev() {
+            let line = self
+                .stacks
+                .iter()
+                .zip(&widths)
+                .map(|(stack, &width)| match stack.crates.get(level) {
+                    Some(contents) => format!("{:<width$}", format!("[{}]", contents)),
+                    None => " ".repeat(width),
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        let footer = labels
+            .iter()
+            .zip(&widths)
+            .map(|(label, &width)| format!(" {:<w$}", label, w = width - 1))
+            .collect::<Vec<_>>()
+            .join(" ");
+        write!(f, "{}", footer.trim_end())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(drawing: &str) -> Result<CargoLoad, DrawingError> {
+        drawing.parse()
+    }
+
+    // stacks given bottom to top
+    fn load<S: AsRef<str>>(stacks: &[S]) -> CargoLoad {
+        CargoLoad {
+            stacks: stacks
+                .iter()
+                .map(|crates| CrateStack {
+                    crates: crates.as_ref().chars().collect(),
+                })
+                .collect(),
+        }
+    }
+
+    fn assert_round_trip(load: CargoLoad) {
+        assert_eq!(parse(&load.to_string()), Ok(load));
+    }
+
+    #[test]
+    fn round_trip_multi_digit_labels() {
+        let stacks = (0..12)
+            .map(|idx| "ABCDEFG".chars().take(idx % 5 + 1).collect::<String>())
+            .collect::<Vec<_>>();
+        assert_round_trip(load(&stacks));
+    }
+
+    #[test]
+    fn round_trip_wide_columns() {
+        let stacks = (0..120)
+            .map(|idx| {
+                (0..idx % 7)
+                    .map(|level| (b'A' + ((idx + level) % 26) as u8) as char)
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        assert_round_trip(load(&stacks));
+    }
+
+    #[test]
+    fn round_trip_empty_stacks() {
+        assert_round_trip(load(&["", "AB", "", "C", ""]));
+        assert_round_trip(load(&["", "", ""]));
+    }
+
+    #[test]
+    fn ragged_lines() {
+        let trimmed = "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3";
+        let padded = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 ";
+        let expected = load(&["ZN", "MCD", "P"]);
+        assert_eq!(parse(trimmed), Ok(expected.clone()));
+        assert_eq!(parse(padded), Ok(expected.clone()));
+        assert_round_trip(expected);
+    }
+
+    #[test]
+    fn footer_errors() {
+        assert_eq!(parse(""), Err(DrawingError::Empty));
+        assert_eq!(parse("\n   \n"), Err(DrawingError::Empty));
+        assert_eq!(
+            parse(" 1  x"),
+            Err(DrawingError::InvalidLabel { label: "x".into() })
+        );
+        assert_eq!(
+            parse(" 1  3"),
+            Err(DrawingError::UnexpectedLabel {
+                expected: 2,
+                found: 3
+            })
+        );
+    }
+
+    #[test]
+    fn crate_errors_count_blank_lines() {
+        assert_eq!(
+            parse("\n[A] (B\n 1   2"),
+            Err(DrawingError::InvalidCrate { line: 2, column: 5 })
+        );
+        assert_eq!(
+            parse("[A]\n\n    [B] [C]\n 1   2"),
+            Err(DrawingError::UnlabelledCrate { line: 3, column: 9 })
+        );
+        // both crates touch the two-digit label
+        let footer = (1..=10)
+            .map(|label| format!("{:<3}", label))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let drawing = format!("  \n{}[A][B]\n {}", " ".repeat(35), footer);
+        assert_eq!(
+            parse(&drawing),
+            Err(DrawingError::DuplicateCrate {
+                line: 2,
+                column: 39
+            })
+        );
+    }
+}
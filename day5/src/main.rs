@@ -1,11 +1,18 @@
-use std::{collections::VecDeque, fs, str::FromStr, string::ParseError};
+use std::{collections::VecDeque, env, fmt, fs, process, str::FromStr, time::Duration};
 
-#[derive(Debug, Default)]
+use crane::{Crane, InstructionError};
+
+mod animate;
+mod crane;
+mod drawing;
+mod planner;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
 struct CrateStack {
     crates: VecDeque<char>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct CargoLoad {
     stacks: Vec<CrateStack>,
 }
@@ -17,90 +24,232 @@ struct Instruction {
     count: usize,
 }
 
-impl FromStr for CargoLoad {
-    type Err = ParseError;
+#[derive(Debug, PartialEq)]
+enum ParseInstructionError {
+    /// Not of the form `move <count> from <stack> to <stack>`.
+    Malformed,
+    InvalidNumber {
+        word: String,
+    },
+    /// Stacks are numbered from 1, so there is no stack 0.
+    NoStackZero,
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut lines = s.lines();
-        let first_line = lines.next().unwrap();
-        let num_stacks = first_line.split(" ").filter(|x| !x.is_empty()).count();
-        let mut stacks = Vec::<CrateStack>::new();
-        stacks.resize_with(num_stacks, Default::default);
-        for line in lines {
-            let chunks = line
-                .as_bytes()
-                .chunks(4)
-                .map(|chunk| chunk[1] as char)
-                .enumerate()
-                .filter(|(_, item)| item != &' ');
-            for (idx, contents) in chunks {
-                stacks[idx].crates.push_back(contents)
+impl fmt::Display for ParseInstructionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseInstructionError::Malformed => {
+                write!(f, "expected `move <count> from <stack> to <stack>`")
             }
+            ParseInstructionError::InvalidNumber { word } => {
+                write!(f, "{:?} is not a number", word)
+            }
+            ParseInstructionError::NoStackZero => write!(f, "stacks are numbered from 1"),
         }
-        return Ok(CargoLoad { stacks });
     }
 }
 
+impl std::error::Error for ParseInstructionError {}
+
 impl FromStr for Instruction {
-    type Err = ParseError;
+    type Err = ParseInstructionError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let parts = s.split(" ").collect::<Vec<_>>();
+        if parts.len() != 6 || parts[0] != "move" || parts[2] != "from" || parts[4] != "to" {
+            return Err(ParseInstructionError::Malformed);
+        }
+        let number = |word: &str| {
+            word.parse::<usize>()
+                .map_err(|_| ParseInstructionError::InvalidNumber { word: word.into() })
+        };
+        // stacks are stored 0-based
+        let stack = |word: &str| {
+            number(word)?
+                .checked_sub(1)
+                .ok_or(ParseInstructionError::NoStackZero)
+        };
 
         Ok(Instruction {
-            source_stack: parts[3].parse::<usize>().unwrap() - 1,
-            dest_stack: parts[5].parse::<usize>().unwrap() - 1,
-            count: parts[1].parse().unwrap(),
+            source_stack: stack(parts[3])?,
+            dest_stack: stack(parts[5])?,
+            count: number(parts[1])?,
         })
     }
 }
 
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.count,
+            self.source_stack + 1,
+            self.dest_stack + 1
+        )
+    }
+}
+
 impl CargoLoad {
-    fn apply_instruction(&mut self, instruction: &Instruction, part2: bool) {
+    /// Checks both stacks exist and the source has enough crates for the move.
+    fn validate(&self, instruction: &Instruction) -> Result<(), InstructionError> {
+        for stack in [instruction.source_stack, instruction.dest_stack] {
+            if stack >= self.stacks.len() {
+                return Err(InstructionError::NoSuchStack { stack });
+            }
+        }
+        let available = self.stacks[instruction.source_stack].crates.len();
+        if available < instruction.count {
+            return Err(InstructionError::NotEnoughCrates {
+                stack: instruction.source_stack,
+                requested: instruction.count,
+                available,
+            });
+        }
+        Ok(())
+    }
+
+    fn apply_instruction(
+        &mut self,
+        instruction: &Instruction,
+        crane: &dyn Crane,
+    ) -> Result<(), InstructionError> {
+        self.validate(instruction)?;
         let stacks = &mut self.stacks;
 
-        let source_stack = stacks.get_mut(instruction.source_stack).unwrap();
-        let start_index = source_stack.crates.len() - instruction.count;
-        let mut items = source_stack
-            .crates
-            .drain(start_index..)
-            .collect::<VecDeque<_>>();
-        if !part2 {
-            items = items.into_iter().rev().collect::<VecDeque<char>>();
+        for lift in crane.lifts(instruction.count) {
+            let source_stack = stacks.get_mut(instruction.source_stack).unwrap();
+            let start_index = source_stack.crates.len() - lift;
+            let mut items = source_stack
+                .crates
+                .drain(start_index..)
+                .collect::<VecDeque<_>>();
+            stacks
+                .get_mut(instruction.dest_stack)
+                .unwrap()
+                .crates
+                .append(&mut items);
         }
-        stacks
-            .get_mut(instruction.dest_stack)
-            .unwrap()
-            .crates
-            .append(&mut items);
+        Ok(())
+    }
+
+    /// The crate on top of each stack, with a space for empty stacks.
+    fn top_crates(&self) -> String {
+        self.stacks
+            .iter()
+            .map(|stack| stack.crates.back().copied().unwrap_or(' '))
+            .collect()
     }
 }
 
+fn arg_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    let position = args.iter().position(|arg| arg == name)?;
+    args.get(position + 1).map(String::as_str)
+}
+
+fn number_arg<T>(args: &[String], name: &str, default: T) -> T
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    if !args.iter().any(|arg| arg == name) {
+        return default;
+    }
+    let value = arg_value(args, name).unwrap_or("");
+    value.parse().unwrap_or_else(|err| {
+        eprintln!("{} {:?}: {}", name, value, err);
+        process::exit(1);
+    })
+}
+
 fn main() {
+    // day5 [--crane 9000|9001|limited:<capacity>] [--delay <ms>]
+    //      [--animate | --frames <dir> | --svg <file>]
+    //      [--plan-tops <crates> | --plan-target <drawing file>] [--max-nodes <n>]
+    let args = env::args().collect::<Vec<_>>();
+    let crane_name = arg_value(&args, "--crane").unwrap_or("9001");
+    let crane = crane::from_name(crane_name).unwrap_or_else(|| {
+        eprintln!("unknown crane {:?}", crane_name);
+        process::exit(1);
+    });
+
     let contents = fs::read_to_string("input/day5.txt").unwrap();
     let mut iter = contents.lines();
     let cargo = iter
         .by_ref()
         .take_while(|l| !l.is_empty())
         .collect::<Vec<_>>()
-        .into_iter()
-        .rev()
-        .collect::<Vec<_>>()
         .join("\n");
     let instructions = iter
-        .map(|s| s.parse::<Instruction>().unwrap())
+        .enumerate()
+        .map(|(idx, s)| {
+            s.parse::<Instruction>().unwrap_or_else(|err| {
+                eprintln!("instruction {}: {}", idx + 1, err);
+                process::exit(1);
+            })
+        })
         .collect::<Vec<_>>();
-    let mut cargo_load: CargoLoad = cargo.parse().unwrap();
-    println!("{:?}", cargo_load);
-    for inst in instructions {
-        cargo_load.apply_instruction(&inst, true);
+    let mut cargo_load: CargoLoad = cargo.parse().unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
+    println!("{}", cargo_load);
+
+    let plan_goal = if let Some(tops) = arg_value(&args, "--plan-tops") {
+        Some(planner::Goal::Tops(tops.chars().collect()))
+    } else {
+        arg_value(&args, "--plan-target").map(|path| {
+            let drawing = fs::read_to_string(path).unwrap();
+            planner::Goal::Arrangement(drawing.parse().unwrap_or_else(|err| {
+                eprintln!("{}: {}", path, err);
+                process::exit(1);
+            }))
+        })
+    };
+    if let Some(goal) = plan_goal {
+        let max_nodes = number_arg(&args, "--max-nodes", 100_000);
+        match planner::plan(&cargo_load, &goal, crane.as_ref(), max_nodes) {
+            Ok(plan) => {
+                println!("plan with {} for {} instructions", crane.name(), plan.len());
+                for instruction in &plan {
+                    println!("{}", instruction);
+                }
+            }
+            Err(err) => {
+                eprintln!("{}", err);
+                process::exit(1);
+            }
+        }
     }
-    println!(
-        "{:?}",
-        cargo_load
-            .stacks
-            .iter()
-            .map(|stack| stack.crates.back().unwrap())
-            .collect::<String>()
-    );
+
+    let animate = args.iter().any(|arg| arg == "--animate");
+    let frames_dir = arg_value(&args, "--frames");
+    let svg_path = arg_value(&args, "--svg");
+    if animate || frames_dir.is_some() || svg_path.is_some() {
+        let delay = Duration::from_millis(number_arg(&args, "--delay", 200));
+        let frames = animate::frames(&cargo_load, &instructions, crane.as_ref()).unwrap_or_else(
+            |(idx, err)| {
+                eprintln!("instruction {}: {}", idx + 1, err);
+                process::exit(1);
+            },
+        );
+        let res = if animate {
+            animate::play_terminal(&frames, delay)
+        } else if let Some(dir) = frames_dir {
+            animate::write_text_frames(&frames, dir)
+        } else {
+            animate::write_svg(&frames, svg_path.unwrap(), delay)
+        };
+        if let Err(err) = res {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    }
+    for (idx, inst) in instructions.iter().enumerate() {
+        if let Err(err) = cargo_load.apply_instruction(inst, crane.as_ref()) {
+            eprintln!("instruction {}: {}", idx + 1, err);
+            process::exit(1);
+        }
+    }
+    println!("{}: {:?}", crane.name(), cargo_load.top_crates());
 }
@@ -0,0 +1,205 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    fmt,
+};
+
+use crate::{crane::Crane, CargoLoad, Instruction};
+
+/// What the planner is asked to reach.
+pub enum Goal {
+    /// The crate on top of each stack, with `' '` for an empty stack and `'*'` for any.
+    Tops(Vec<char>),
+    /// Exactly this arrangement.
+    Arrangement(CargoLoad),
+}
+
+impl Goal {
+    fn is_reached(&self, load: &CargoLoad) -> bool {
+        self.unmet(load) == 0
+    }
+
+    // number of stacks not yet as required
+    fn unmet(&self, load: &CargoLoad) -> usize {
+        match self {
+            Goal::Tops(tops) => load
+                .top_crates()
+                .chars()
+                .zip(tops)
+                .filter(|(top, wanted)| **wanted != '*' && top != *wanted)
+                .count(),
+            Goal::Arrangement(target) => load
+                .stacks
+                .iter()
+                .zip(&target.stacks)
+                .filter(|(stack, wanted)| stack != wanted)
+                .count(),
+        }
+    }
+
+    /// A lower bound on the instructions still needed: each instruction only changes
+    /// its source and destination stacks, so it fixes at most two of them.
+    fn heuristic(&self, load: &CargoLoad) -> usize {
+        self.unmet(load).div_ceil(2)
+    }
+
+    // rules out goals that no sequence of moves can reach, before searching
+    fn check(&self, load: &CargoLoad) -> Result<(), PlanError> {
+        let expected = match self {
+            Goal::Tops(tops) => tops.len(),
+            Goal::Arrangement(target) => target.stacks.len(),
+        };
+        if expected != load.stacks.len() {
+            return Err(PlanError::StackCountMismatch {
+                expected: load.stacks.len(),
+                found: expected,
+            });
+        }
+
+        let available = crate_counts(load);
+        let reachable = match self {
+            // every crate wanted on top has to exist, as many times as it is wanted
+            Goal::Tops(tops) => {
+                let mut wanted = HashMap::<char, usize>::new();
+                for &top in tops.iter().filter(|&&top| top != '*' && top != ' ') {
+                    *wanted.entry(top).or_default() += 1;
+                }
+                wanted
+                    .iter()
+                    .all(|(top, count)| available.get(top).is_some_and(|have| have >= count))
+            }
+            Goal::Arrangement(target) => available == crate_counts(target),
+        };
+        if !reachable {
+            return Err(PlanError::Unreachable);
+        }
+        Ok(())
+    }
+}
+
+fn crate_counts(load: &CargoLoad) -> HashMap<char, usize> {
+    let mut counts = HashMap::new();
+    for contents in load.stacks.iter().flat_map(|stack| &stack.crates) {
+        *counts.entry(*contents).or_default() += 1;
+    }
+    counts
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PlanError {
+    StackCountMismatch {
+        expected: usize,
+        found: usize,
+    },
+    /// The search gave up after expanding this many arrangements.
+    NodeLimit(usize),
+    Unreachable,
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanError::StackCountMismatch { expected, found } => write!(
+                f,
+                "the load has {} stacks but the goal has {}",
+                expected, found
+            ),
+            PlanError::NodeLimit(max_nodes) => {
+                write!(f, "gave up after expanding {} arrangements", max_nodes)
+            }
+            PlanError::Unreachable => write!(f, "the goal cannot be reached"),
+        }
+    }
+}
+
+impl std::error::Error for PlanError {}
+
+struct Node {
+    load: CargoLoad,
+    parent: Option<(usize, Instruction)>,
+    cost: usize,
+}
+
+/// Every instruction the crane can carry out from `load`.
+fn moves(load: &CargoLoad) -> Vec<Instruction> {
+    let mut moves = Vec::new();
+    for (source_stack, source) in load.stacks.iter().enumerate() {
+        for dest_stack in 0..load.stacks.len() {
+            if dest_stack == source_stack {
+                continue;
+            }
+            for count in 1..=source.crates.len() {
+                moves.push(Instruction {
+                    source_stack,
+                    dest_stack,
+                    count,
+                });
+            }
+        }
+    }
+    moves
+}
+
+/// A* search for a shortest list of instructions taking `start` to the goal with the
+/// given crane, expanding at most `max_nodes` arrangements.
+pub fn plan(
+    start: &CargoLoad,
+    goal: &Goal,
+    crane: &dyn Crane,
+    max_nodes: usize,
+) -> Result<Vec<Instruction>, PlanError> {
+    goal.check(start)?;
+
+    let mut nodes = vec![Node {
+        load: start.clone(),
+        parent: None,
+        cost: 0,
+    }];
+    let mut best_cost = HashMap::from([(start.clone(), 0usize)]);
+    // ordered by estimated total cost, then by most progress made, then oldest first
+    let mut open = BinaryHeap::from([Reverse((goal.heuristic(start), 0usize, 0usize))]);
+    let mut expanded = 0;
+
+    while let Some(Reverse((_, _, node_idx))) = open.pop() {
+        let cost = nodes[node_idx].cost;
+        if best_cost.get(&nodes[node_idx].load) != Some(&cost) {
+            // a cheaper way here was found after this entry was queued
+            continue;
+        }
+        if goal.is_reached(&nodes[node_idx].load) {
+            return Ok(path(nodes, node_idx));
+        }
+        expanded += 1;
+        if expanded > max_nodes {
+            return Err(PlanError::NodeLimit(max_nodes));
+        }
+
+        for instruction in moves(&nodes[node_idx].load) {
+            let mut load = nodes[node_idx].load.clone();
+            load.apply_instruction(&instruction, crane).unwrap();
+            if best_cost.get(&load).is_some_and(|&known| known <= cost + 1) {
+                continue;
+            }
+            let estimate = cost + 1 + goal.heuristic(&load);
+            let remaining = goal.heuristic(&load);
+            best_cost.insert(load.clone(), cost + 1);
+            nodes.push(Node {
+                load,
+                parent: Some((node_idx, instruction)),
+                cost: cost + 1,
+            });
+            open.push(Reverse((estimate, remaining, nodes.len() - 1)));
+        }
+    }
+    Err(PlanError::Unreachable)
+}
+
+fn path(mut nodes: Vec<Node>, mut node_idx: usize) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    while let Some((parent, instruction)) = nodes[node_idx].parent.take() {
+        instructions.push(instruction);
+        node_idx = parent;
+    }
+    instructions.reverse();
+    instructions
+}
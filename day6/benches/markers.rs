@@ -0,0 +1,31 @@
+use std::{env, hint::black_box, time::Instant};
+
+use common::rng::Rng;
+use day6::search::all;
+
+fn random_stream(rng: &mut Rng, len: usize, alphabet: &[u8]) -> Vec<u8> {
+    (0..len).map(|_| *rng.choose(alphabet).unwrap()).collect()
+}
+
+fn main() {
+    let megabytes = env::args()
+        .skip(1)
+        .find_map(|arg| arg.parse::<usize>().ok())
+        .unwrap_or(8);
+    let mut rng = Rng::new(6);
+    let searches = all();
+
+    for window in [4, 14] {
+        // one letter short of the window, so the only marker is the one at the very end
+        let alphabet = (b'a'..b'a' + window as u8 - 1).collect::<Vec<_>>();
+        let mut stream = random_stream(&mut rng, megabytes << 20, &alphabet);
+        stream.extend(b'a'..b'a' + window as u8);
+        println!("window {} over {} MiB", window, megabytes);
+        for search in &searches {
+            let start = Instant::now();
+            let found = black_box(search.find(black_box(&stream), window));
+            assert_eq!(found, Some(stream.len()));
+            println!("  {:<20} {:>12?}", search.name(), start.elapsed());
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use std::ops::Range;
+
+use crate::marker::{find_marker, START_OF_MESSAGE, START_OF_PACKET};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameKind {
+    Packet,
+    Message,
+}
+
+/// A marker and the payload following it, as offsets into the datastream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub kind: FrameKind,
+    pub marker: Range<usize>,
+    pub payload: Range<usize>,
+}
+
+impl Frame {
+    pub fn payload<'a>(&self, stream: &'a [u8]) -> &'a [u8] {
+        &stream[self.payload.clone()]
+    }
+}
+
+/// Splits the datastream into packets, each carrying a message.
+///
+/// A start-of-packet marker begins a packet, and the first start-of-message marker after
+/// it begins the message the packet carries. The next start-of-packet marker is only
+/// searched for after that message's marker, so the markers inside a message do not cut
+/// it short. A packet's payload runs up to the next packet or the end of the stream, and
+/// its message's payload runs to the end of the packet. A packet with no message marker
+/// after it is the last one and runs to the end of the stream. Frames are sorted by the
+/// offset of their marker.
+pub fn frames(stream: &[u8]) -> Vec<Frame> {
+    // the marker of `window` bytes ending first after `from`, which it does not overlap
+    let marker_after = |from: usize, window: usize| {
+        find_marker(&stream[from..], window).map(|end| from + end - window..from + end)
+    };
+    let mut frames = Vec::new();
+    let mut next_packet = marker_after(0, START_OF_PACKET);
+    while let Some(packet) = next_packet {
+        let message = marker_after(packet.end, START_OF_MESSAGE);
+        next_packet = message
+            .as_ref()
+            .and_then(|message| marker_after(message.end, START_OF_PACKET));
+        let end = next_packet.as_ref().map_or(stream.len(), |next| next.start);
+        frames.push(Frame {
+            kind: FrameKind::Packet,
+            marker: packet.clone(),
+            payload: packet.end..end,
+        });
+        if let Some(message) = message {
+            frames.push(Frame {
+                kind: FrameKind::Message,
+                marker: message.clone(),
+                payload: message.end..end,
+            });
+        }
+    }
+    frames
+}
@@ -0,0 +1,4 @@
+pub mod framing;
+pub mod marker;
+pub mod search;
+pub mod stream;
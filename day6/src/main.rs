@@ -1,24 +1,76 @@
-use std::{fs, process::exit};
+use std::{env, fs, io, process};
+
+use day6::{
+    framing::{frames, FrameKind},
+    marker::{find_packet_and_message, START_OF_MESSAGE, START_OF_PACKET},
+    stream::{ChunkedReader, MarkerDetector},
+};
 
 fn main() {
+    // day6 [--stream [--chunk-size <bytes>] | --frames]
+    let args = env::args().collect::<Vec<_>>();
+    if args.iter().any(|arg| arg == "--stream") {
+        // read the datastream from stdin, reporting markers as they arrive
+        let chunk_size =
+            args.iter()
+                .position(|arg| arg == "--chunk-size")
+                .map_or(4096, |position| {
+                    let value = args.get(position + 1).map(String::as_str).unwrap_or("");
+                    match value.parse() {
+                        Ok(bytes) if bytes >= 1 => bytes,
+                        _ => {
+                            eprintln!(
+                                "--chunk-size needs a byte count of at least 1, got {:?}",
+                                value
+                            );
+                            process::exit(1);
+                        }
+                    }
+                });
+        let mut detector = MarkerDetector::new(&[START_OF_PACKET, START_OF_MESSAGE]);
+        let res = detector.feed(
+            ChunkedReader::new(io::stdin().lock(), chunk_size),
+            |event| {
+                println!(
+                    "{}-byte marker {:?} after {}",
+                    event.window,
+                    String::from_utf8_lossy(&event.marker),
+                    event.end
+                );
+            },
+        );
+        if let Err(err) = res {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+        println!("read {} bytes", detector.position());
+        return;
+    }
+
     let contents = fs::read_to_string("input/day6.txt").unwrap();
-    let mut found_start = String::new();
-    for (start_idx, c) in contents.chars().enumerate() {
-        let found = found_start.find(c);
-        let cur_len = found_start.len();
-        match (found, cur_len) {
-            (None, 13) => {
-                found_start = found_start + &c.to_string();
-                println!("found: {} {}", found_start, start_idx + 1);
-                exit(0);
-            }
-            (Some(idx), _) => {
-                found_start = found_start[idx + 1..].to_owned() + &c.to_string();
-            }
-            (None, _) => {
-                found_start = found_start + &c.to_string();
-            }
+    let (packet, message) = find_packet_and_message(contents.trim_end().as_bytes());
+    println!("start-of-packet marker after {:?}", packet);
+    println!("start-of-message marker after {:?}", message);
+
+    if args.iter().any(|arg| arg == "--frames") {
+        let stream = contents.trim_end().as_bytes();
+        for frame in frames(stream) {
+            let kind = match frame.kind {
+                FrameKind::Packet => "packet",
+                FrameKind::Message => "message",
+            };
+            let payload = frame.payload(stream);
+            println!(
+                "{:<7} marker {:>5}..{:<5} {:?} payload {:>5}..{:<5} ({} bytes) {:?}",
+                kind,
+                frame.marker.start,
+                frame.marker.end,
+                String::from_utf8_lossy(&stream[frame.marker.clone()]),
+                frame.payload.start,
+                frame.payload.end,
+                payload.len(),
+                String::from_utf8_lossy(&payload[..payload.len().min(16)])
+            );
         }
-        println!("current start {}", found_start);
     }
 }
@@ -0,0 +1,110 @@
+pub const START_OF_PACKET: usize = 4;
+pub const START_OF_MESSAGE: usize = 14;
+
+/// The last `window` bytes of a stream, with how many times each byte occurs in them and
+/// how many bytes occur more than once, so checking for duplicates is O(1) per byte.
+/// Memory use only depends on the window size.
+#[derive(Debug, Clone)]
+pub struct SlidingWindow {
+    window: usize,
+    ring: Vec<u8>,
+    next: usize,
+    len: usize,
+    counts: [u32; 256],
+    duplicates: usize,
+}
+
+impl SlidingWindow {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window,
+            ring: vec![0; window],
+            next: 0,
+            len: 0,
+            counts: [0; 256],
+            duplicates: 0,
+        }
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Slides the window over `byte`, returning whether it is now full and has no
+    /// repeated byte.
+    pub fn push(&mut self, byte: u8) -> bool {
+        if self.window == 0 {
+            return true;
+        }
+        if self.len == self.window {
+            let leaving = self.ring[self.next];
+            if self.counts[leaving as usize] == 2 {
+                self.duplicates -= 1;
+            }
+            self.counts[leaving as usize] -= 1;
+        } else {
+            self.len += 1;
+        }
+        self.ring[self.next] = byte;
+        self.next = (self.next + 1) % self.window;
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 2 {
+            self.duplicates += 1;
+        }
+        self.len == self.window && self.duplicates == 0
+    }
+
+    /// The bytes in the window, oldest first.
+    pub fn contents(&self) -> Vec<u8> {
+        let start = (self.next + self.window - self.len) % self.window.max(1);
+        (0..self.len)
+            .map(|offset| self.ring[(start + offset) % self.window])
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.next = 0;
+        self.len = 0;
+        self.counts = [0; 256];
+        self.duplicates = 0;
+    }
+}
+
+/// Number of bytes read until the last `window` bytes are all different, i.e. the
+/// position just after the first marker of that size.
+pub fn find_marker(stream: &[u8], window: usize) -> Option<usize> {
+    find_markers(stream, &[window])[0]
+}
+
+/// Same as [`find_marker`] for several window sizes at once, in a single pass over the
+/// stream that stops as soon as every marker has been found.
+pub fn find_markers(stream: &[u8], windows: &[usize]) -> Vec<Option<usize>> {
+    let mut sliding = windows
+        .iter()
+        .map(|&window| SlidingWindow::new(window))
+        .collect::<Vec<_>>();
+    let mut found = windows
+        .iter()
+        .map(|&window| if window == 0 { Some(0) } else { None })
+        .collect::<Vec<_>>();
+    let mut remaining = found.iter().filter(|found_at| found_at.is_none()).count();
+
+    for (idx, &byte) in stream.iter().enumerate() {
+        if remaining == 0 {
+            break;
+        }
+        for (window, found_at) in sliding.iter_mut().zip(found.iter_mut()) {
+            if found_at.is_none() && window.push(byte) {
+                *found_at = Some(idx + 1);
+                remaining -= 1;
+            }
+        }
+    }
+    found
+}
+
+/// Positions after the start-of-packet and start-of-message markers.
+pub fn find_packet_and_message(stream: &[u8]) -> (Option<usize>, Option<usize>) {
+    let found = find_markers(stream, &[START_OF_PACKET, START_OF_MESSAGE]);
+    (found[0], found[1])
+}
@@ -0,0 +1,208 @@
+use std::collections::HashSet;
+
+use crate::marker::find_marker;
+
+/// A way of finding the first window of distinct bytes. Every implementation returns the
+/// number of bytes read when the marker is complete, like [`find_marker`].
+pub trait MarkerSearch {
+    fn name(&self) -> &'static str;
+    fn find(&self, stream: &[u8], window: usize) -> Option<usize>;
+}
+
+/// Compares every pair of bytes in every window.
+pub struct NaiveScan;
+
+/// Builds a `HashSet` from every window.
+pub struct HashSetWindow;
+
+/// Slides a window of byte counts along the stream.
+pub struct CountArray;
+
+/// Slides a 256-bit mask along the stream, toggling the bit of every byte entering and
+/// leaving the window. A byte seen twice clears its bit again, so the window is distinct
+/// exactly when the number of set bits equals the window size.
+pub struct XorBitmask;
+
+/// Checks each window from its end backwards, OR-ing bytes into a bitmask. On finding a
+/// repeated byte, no window containing both copies can be a marker, so the search jumps
+/// to start just after the earlier copy, skipping most windows without looking at them.
+pub struct SkipScan;
+
+/// Tracks where the run of distinct bytes ending at each byte starts, taking the max of
+/// it and one past the previous copy of the byte, and sets one bit per byte of a 64-byte
+/// block where the run reaches the window. Nothing in the loop over a block branches on
+/// the data; the only test is whether the block's mask has any bit set.
+pub struct BranchFree;
+
+pub fn all() -> Vec<Box<dyn MarkerSearch>> {
+    vec![
+        Box::new(NaiveScan),
+        Box::new(HashSetWindow),
+        Box::new(CountArray),
+        Box::new(XorBitmask),
+        Box::new(SkipScan),
+        Box::new(BranchFree),
+    ]
+}
+
+impl MarkerSearch for NaiveScan {
+    fn name(&self) -> &'static str {
+        "naive window scan"
+    }
+
+    fn find(&self, stream: &[u8], window: usize) -> Option<usize> {
+        if window == 0 {
+            return Some(0);
+        }
+        stream
+            .windows(window)
+            .position(|bytes| {
+                (0..bytes.len()).all(|i| (i + 1..bytes.len()).all(|j| bytes[i] != bytes[j]))
+            })
+            .map(|start| start + window)
+    }
+}
+
+impl MarkerSearch for HashSetWindow {
+    fn name(&self) -> &'static str {
+        "HashSet per window"
+    }
+
+    fn find(&self, stream: &[u8], window: usize) -> Option<usize> {
+        if window == 0 {
+            return Some(0);
+        }
+        stream
+            .windows(window)
+            .position(|bytes| bytes.iter().collect::<HashSet<_>>().len() == window)
+            .map(|start| start + window)
+    }
+}
+
+impl MarkerSearch for CountArray {
+    fn name(&self) -> &'static str {
+        "count array"
+    }
+
+    fn find(&self, stream: &[u8], window: usize) -> Option<usize> {
+        find_marker(stream, window)
+    }
+}
+
+impl MarkerSearch for XorBitmask {
+    fn name(&self) -> &'static str {
+        "XOR bitmask"
+    }
+
+    fn find(&self, stream: &[u8], window: usize) -> Option<usize> {
+        if window == 0 {
+            return Some(0);
+        }
+        let mut mask = [0u64; 4];
+        let toggle = |mask: &mut [u64; 4], byte: u8| mask[byte as usize >> 6] ^= 1 << (byte & 63);
+        for (idx, &byte) in stream.iter().enumerate() {
+            toggle(&mut mask, byte);
+            if idx >= window {
+                toggle(&mut mask, stream[idx - window]);
+            }
+            let set_bits = mask.iter().map(|word| word.count_ones()).sum::<u32>();
+            if set_bits as usize == window {
+                return Some(idx + 1);
+            }
+        }
+        None
+    }
+}
+
+impl MarkerSearch for SkipScan {
+    fn name(&self) -> &'static str {
+        "bitmask skip scan"
+    }
+
+    fn find(&self, stream: &[u8], window: usize) -> Option<usize> {
+        if window == 0 {
+            return Some(0);
+        }
+        let mut start = 0;
+        'windows: while start + window <= stream.len() {
+            let mut seen = [0u64; 4];
+            for idx in (start..start + window).rev() {
+                let (word, bit) = (stream[idx] as usize >> 6, 1u64 << (stream[idx] & 63));
+                if seen[word] & bit != 0 {
+                    start = idx + 1;
+                    continue 'windows;
+                }
+                seen[word] |= bit;
+            }
+            return Some(start + window);
+        }
+        None
+    }
+}
+
+impl MarkerSearch for BranchFree {
+    fn name(&self) -> &'static str {
+        "branch-free run mask"
+    }
+
+    fn find(&self, stream: &[u8], window: usize) -> Option<usize> {
+        if window == 0 {
+            return Some(0);
+        }
+        // one past the last position of every byte, 0 if not seen yet
+        let mut after_last = [0usize; 256];
+        let mut run_start = 0;
+        for (block, bytes) in stream.chunks(64).enumerate() {
+            let mut marker_ends = 0u64;
+            for (offset, &byte) in bytes.iter().enumerate() {
+                let end = block * 64 + offset + 1;
+                run_start = run_start.max(after_last[byte as usize]);
+                after_last[byte as usize] = end;
+                marker_ends |= u64::from(end - run_start >= window) << offset;
+            }
+            if marker_ends != 0 {
+                return Some(block * 64 + marker_ends.trailing_zeros() as usize + 1);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::rng::Rng;
+
+    use super::*;
+
+    // every implementation has to agree on many small random streams, with alphabets
+    // around the window size so that markers are sometimes found and sometimes not
+    #[test]
+    fn implementations_agree() {
+        let searches = all();
+        let mut rng = Rng::new(6);
+        for _ in 0..20_000 {
+            let window = rng.range_inclusive(0, 16) as usize;
+            let alphabet_len = rng.range_inclusive(1, 20) as usize;
+            let alphabet = (0..alphabet_len)
+                .map(|_| rng.below(256) as u8)
+                .collect::<Vec<_>>();
+            // long enough to cross a few of the 64-byte blocks of `BranchFree`
+            let len = rng.range_inclusive(0, 200) as usize;
+            let stream = (0..len)
+                .map(|_| *rng.choose(&alphabet).unwrap())
+                .collect::<Vec<_>>();
+            let expected = searches[0].find(&stream, window);
+            for search in &searches[1..] {
+                assert_eq!(
+                    search.find(&stream, window),
+                    expected,
+                    "{} disagrees with {} on window {} of {:?}",
+                    search.name(),
+                    searches[0].name(),
+                    window,
+                    stream
+                );
+            }
+        }
+    }
+}
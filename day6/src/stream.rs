@@ -0,0 +1,99 @@
+use std::io::{self, Read};
+
+use crate::marker::SlidingWindow;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkerEvent {
+    /// Size of the marker, e.g. 4 for start-of-packet.
+    pub window: usize,
+    /// Number of bytes of the stream read when the marker was complete.
+    pub end: usize,
+    pub marker: Vec<u8>,
+}
+
+/// Finds markers in a stream that arrives in chunks of any size, using memory that only
+/// depends on the marker sizes.
+///
+/// Markers of the same size never overlap: once one is found, the next one of that size
+/// has to be made of bytes that come after it.
+#[derive(Debug, Clone)]
+pub struct MarkerDetector {
+    windows: Vec<SlidingWindow>,
+    position: usize,
+}
+
+impl MarkerDetector {
+    pub fn new(windows: &[usize]) -> Self {
+        assert!(
+            windows.iter().all(|&window| window > 0),
+            "markers must be at least one byte"
+        );
+        Self {
+            windows: windows.iter().map(|&w| SlidingWindow::new(w)).collect(),
+            position: 0,
+        }
+    }
+
+    /// Number of bytes pushed so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Feeds the next chunk of the stream, returning the markers it completes in order.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<MarkerEvent> {
+        let mut events = Vec::new();
+        for &byte in chunk {
+            self.position += 1;
+            for window in self.windows.iter_mut() {
+                if window.push(byte) {
+                    events.push(MarkerEvent {
+                        window: window.window(),
+                        end: self.position,
+                        marker: window.contents(),
+                    });
+                    window.clear();
+                }
+            }
+        }
+        events
+    }
+
+    /// Reads `reader` to the end in fixed-size chunks, calling `on_event` for every marker.
+    pub fn feed<R, F>(&mut self, mut reader: R, mut on_event: F) -> io::Result<()>
+    where
+        R: Read,
+        F: FnMut(MarkerEvent),
+    {
+        let mut buf = [0u8; 8192];
+        loop {
+            let read = match reader.read(&mut buf) {
+                Ok(0) => return Ok(()),
+                Ok(read) => read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+            self.push(&buf[..read]).into_iter().for_each(&mut on_event);
+        }
+    }
+}
+
+/// Stand-in for a socket: hands out the wrapped reader's data at most `chunk_size` bytes
+/// per read, so consumers have to cope with partial reads.
+pub struct ChunkedReader<R> {
+    inner: R,
+    chunk_size: usize,
+}
+
+impl<R: Read> ChunkedReader<R> {
+    pub fn new(inner: R, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunks must hold at least one byte");
+        Self { inner, chunk_size }
+    }
+}
+
+impl<R: Read> Read for ChunkedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = usize::min(buf.len(), self.chunk_size);
+        self.inner.read(&mut buf[..len])
+    }
+}
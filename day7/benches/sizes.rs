@@ -0,0 +1,154 @@
+use std::{
+    collections::HashMap,
+    env, fs,
+    hint::black_box,
+    process::{Command, Stdio},
+    time::Instant,
+};
+
+use day7::{
+    generate::{generate, GeneratorConfig, SizeDistribution},
+    sizes::{DenseTree, NodeId},
+};
+
+// the previous approach: children keyed by full path, recursing with a path-keyed memo
+struct PathTree {
+    children: HashMap<String, Vec<String>>,
+    file_size: HashMap<String, usize>,
+}
+
+impl PathTree {
+    fn new(tree: &DenseTree) -> Self {
+        let mut children = HashMap::<String, Vec<String>>::new();
+        let mut file_size = HashMap::new();
+        for id in 0..tree.len() as NodeId {
+            let path = tree.path(id);
+            if let Some(parent) = tree.parent(id) {
+                children
+                    .entry(tree.path(parent))
+                    .or_default()
+                    .push(path.clone());
+            }
+            if let Some(size) = tree.file_size(id) {
+                file_size.insert(path, size);
+            }
+        }
+        Self {
+            children,
+            file_size,
+        }
+    }
+
+    fn size(&self, path: &String, memo: &mut HashMap<String, usize>) -> usize {
+        if let Some(&size) = self.file_size.get(path) {
+            return size;
+        }
+        if let Some(&size) = memo.get(path) {
+            return size;
+        }
+        let size = self.children.get(path).map_or(0, |children| {
+            children.iter().map(|child| self.size(child, memo)).sum()
+        });
+        memo.insert(path.clone(), size);
+        size
+    }
+}
+
+fn main() {
+    let nodes = env::args()
+        .skip(1)
+        .find_map(|arg| arg.parse::<usize>().ok())
+        .unwrap_or(1_000_000);
+    let sizes = SizeDistribution::LogUniform {
+        min: 1,
+        max: 1_000_000,
+    };
+    let shapes = [
+        (
+            "wide",
+            GeneratorConfig {
+                max_depth: 64,
+                fan_out: (0, 4),
+                files: (0, 4),
+                sizes,
+                // about two files per directory
+                max_directories: nodes / 3,
+                seed: 50,
+            },
+        ),
+        (
+            "deep",
+            GeneratorConfig {
+                max_depth: usize::MAX,
+                fan_out: (1, 1),
+                files: (1, 1),
+                sizes,
+                max_directories: nodes / 2,
+                seed: 50,
+            },
+        ),
+    ];
+
+    for (shape, config) in shapes {
+        let generated = generate(&config);
+        let expected = &generated.expected;
+        println!(
+            "{}: {} directories, {} files, depth {}",
+            shape, expected.directories, expected.files, expected.max_depth
+        );
+
+        let start = Instant::now();
+        let tree = black_box(&generated.transcript)
+            .parse::<DenseTree>()
+            .unwrap();
+        println!("  {:<24} {:>12?}", "parse", start.elapsed());
+
+        let start = Instant::now();
+        let dense = black_box(tree.sizes());
+        println!("  {:<24} {:>12?}", "dense post-order", start.elapsed());
+        assert_eq!(dense[DenseTree::ROOT as usize], expected.total_size);
+
+        // the binary end to end, from reading the transcript to printing the report; a
+        // deep tree has very long paths, so the plan only needs a few files to be deleted
+        let input = env::temp_dir().join(format!("day7-bench-{}.txt", shape));
+        fs::write(&input, &generated.transcript).unwrap();
+        let disk_size = (2 * expected.total_size).to_string();
+        let required_free = (expected.total_size + 5_000_000).to_string();
+        for (name, args) in [
+            ("day7 --largest 10", vec!["--largest", "10"]),
+            (
+                "day7 cleanup plan",
+                vec!["--disk-size", &disk_size, "--required-free", &required_free],
+            ),
+        ] {
+            let start = Instant::now();
+            let status = Command::new(env!("CARGO_BIN_EXE_day7"))
+                .arg("--input")
+                .arg(&input)
+                .args(args)
+                .stdout(Stdio::null())
+                .status()
+                .unwrap();
+            println!("  {:<24} {:>12?}", name, start.elapsed());
+            assert!(status.success());
+        }
+        fs::remove_file(&input).unwrap();
+
+        // full paths make the old layout quadratic in depth, and it recurses once per
+        // level, so it is only run on the wide tree
+        if expected.max_depth > 1_000 {
+            println!("  {:<24} {:>12}", "recursive path memo", "skipped");
+            continue;
+        }
+        let path_tree = PathTree::new(&tree);
+        let root = "/".to_string();
+        let start = Instant::now();
+        let mut memo = HashMap::new();
+        let total = black_box(path_tree.size(&root, &mut memo));
+        println!("  {:<24} {:>12?}", "recursive path memo", start.elapsed());
+        assert_eq!(total, expected.total_size);
+        for (path, size) in &memo {
+            assert_eq!(dense[tree.id(path).unwrap() as usize], *size);
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use std::{cmp::Reverse, fmt};
+
+use day7::sizes::{DenseTree, NodeId};
+
+#[derive(Debug, PartialEq)]
+pub enum CleanupError {
+    /// The filesystem already uses more than the whole disk.
+    Overfull { used: usize, disk_size: usize },
+}
+
+impl fmt::Display for CleanupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CleanupError::Overfull { used, disk_size } => {
+                write!(f, "{} used on a disk of only {}", used, disk_size)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CleanupError {}
+
+/// Paths to delete together, none inside another, and the space they free.
+#[derive(Debug, PartialEq)]
+pub struct Deletion {
+    pub paths: Vec<(String, usize)>,
+    pub total: usize,
+}
+
+#[derive(Debug)]
+pub struct CleanupPlan {
+    pub disk_size: usize,
+    pub used: usize,
+    /// Space that still has to be freed, zero when there is already enough.
+    pub needed: usize,
+    /// The smallest single directory freeing enough, if any does.
+    pub single: Option<Deletion>,
+    /// The smallest combination of directories and files freeing enough, if any does.
+    pub combined: Option<Deletion>,
+    /// Whether `combined` is proven minimal, rather than the best found in the node budget.
+    pub optimal: bool,
+    pub nodes: usize,
+}
+
+/// Works out what to delete from `/` so at least `required_free` of `disk_size` is free.
+///
+/// Deleting a set of non-nested entries frees the same space as deleting every file
+/// beneath them, so the combined search is a subset sum over file sizes for the smallest
+/// total of at least the space needed. It is a depth-first branch and bound over the
+/// files largest first, stopping early on an exact fit or after `max_nodes` nodes. The
+/// chosen files are reported as the directories they fill completely.
+pub fn plan(
+    tree: &DenseTree,
+    disk_size: usize,
+    required_free: usize,
+    max_nodes: usize,
+) -> Result<CleanupPlan, CleanupError> {
+    let sizes = tree.sizes();
+    let used = sizes[DenseTree::ROOT as usize];
+    if used > disk_size {
+        return Err(CleanupError::Overfull { used, disk_size });
+    }
+    let needed = required_free.saturating_sub(disk_size - used);
+    // ties are broken by path so the plan is the same whatever order entries were listed in
+    let path_order = tree.path_order();
+
+    let single = (0..tree.len() as NodeId)
+        .filter(|&id| tree.is_directory(id) && sizes[id as usize] >= needed)
+        .min_by_key(|&id| (sizes[id as usize], path_order[id as usize]))
+        .map(|id| Deletion {
+            paths: vec![(tree.path(id), sizes[id as usize])],
+            total: sizes[id as usize],
+        });
+
+    let mut files = (0..tree.len() as NodeId)
+        .filter(|&id| !tree.is_directory(id))
+        .collect::<Vec<_>>();
+    files.sort_by_key(|&id| (Reverse(sizes[id as usize]), path_order[id as usize]));
+    let file_sizes = files
+        .iter()
+        .map(|&id| sizes[id as usize])
+        .collect::<Vec<_>>();
+    let search = subset_sum_at_least(&file_sizes, needed, max_nodes);
+    let combined = search.best.map(|(total, chosen)| Deletion {
+        paths: collapse(tree, &sizes, chosen.iter().map(|&idx| files[idx])),
+        total,
+    });
+
+    Ok(CleanupPlan {
+        disk_size,
+        used,
+        needed,
+        single,
+        combined,
+        optimal: search.complete,
+        nodes: search.nodes,
+    })
+}
+
+struct Search {
+    best: Option<(usize, Vec<usize>)>,
+    complete: bool,
+    nodes: usize,
+}
+
+/// Smallest sum of at least `target` over a subset of `sizes`, which are sorted largest
+/// first, returning the indices used.
+fn subset_sum_at_least(sizes: &[usize], target: usize, max_nodes: usize) -> Search {
+    // what is left to take from each index on, to prune branches that cannot reach target
+    let mut remaining = vec![0; sizes.len() + 1];
+    for idx in (0..sizes.len()).rev() {
+        remaining[idx] = remaining[idx + 1] + sizes[idx];
+    }
+    let mut best: Option<(usize, Vec<usize>)> = None;
+    let mut chosen = Vec::new();
+    // (next index, sum so far, chosen length to go back to, index taken on the way in)
+    let mut stack = vec![(0, 0, 0, None)];
+    let mut nodes = 0;
+    while let Some((idx, sum, len, taken)) = stack.pop() {
+        if nodes == max_nodes {
+            return Search {
+                best,
+                complete: false,
+                nodes,
+            };
+        }
+        nodes += 1;
+        chosen.truncate(len);
+        chosen.extend(taken);
+        if sum >= target {
+            if best.as_ref().is_none_or(|(total, _)| sum < *total) {
+                best = Some((sum, chosen.clone()));
+            }
+            if sum == target {
+                break;
+            }
+            continue;
+        }
+        if sum + remaining[idx] < target {
+            continue;
+        }
+        stack.push((idx + 1, sum, chosen.len(), None));
+        let with = sum + sizes[idx];
+        if best.as_ref().is_none_or(|(total, _)| with < *total) {
+            stack.push((idx + 1, with, chosen.len(), Some(idx)));
+        }
+    }
+    Search {
+        best,
+        complete: true,
+        nodes,
+    }
+}
+
+/// Replaces files by the highest directory all of whose files are among them, in
+/// listing order.
+fn collapse(
+    tree: &DenseTree,
+    sizes: &[usize],
+    files: impl Iterator<Item = NodeId>,
+) -> Vec<(String, usize)> {
+    // (files beneath, chosen files beneath) for every node; children come after their
+    // parent, so going down the ids adds each node into its parent once it is complete
+    let mut counts = (0..tree.len() as NodeId)
+        .map(|id| (usize::from(!tree.is_directory(id)), 0))
+        .collect::<Vec<_>>();
+    for id in files {
+        counts[id as usize].1 = 1;
+    }
+    for id in (1..tree.len()).rev() {
+        let parent = tree.parent(id as NodeId).unwrap() as usize;
+        let (all, taken) = counts[id];
+        counts[parent].0 += all;
+        counts[parent].1 += taken;
+    }
+
+    let mut paths = vec![];
+    let mut stack = vec![DenseTree::ROOT];
+    while let Some(node) = stack.pop() {
+        let (all, taken) = counts[node as usize];
+        if taken == 0 {
+            continue;
+        }
+        if taken == all {
+            paths.push((tree.path(node), sizes[node as usize]));
+            continue;
+        }
+        stack.extend(tree.children(node).iter().rev());
+    }
+    paths
+}
+
+impl fmt::Display for Deletion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = if self.paths.len() == 1 { "" } else { "s" };
+        writeln!(f, "{} in {} path{}", self.total, self.paths.len(), plural)?;
+        for (path, size) in &self.paths {
+            writeln!(f, "  {:>10}  {}", size, path)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for CleanupPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "used {} of {}, free {}, need {} more",
+            self.used,
+            self.disk_size,
+            self.disk_size - self.used,
+            self.needed
+        )?;
+        match &self.single {
+            Some(single) => write!(f, "smallest directory: {}", single)?,
+            None => writeln!(f, "smallest directory: none frees enough")?,
+        }
+        let quality = if self.optimal {
+            "minimal"
+        } else {
+            "best found"
+        };
+        match &self.combined {
+            Some(combined) => {
+                write!(f, "{} set ({} nodes): {}", quality, self.nodes, combined)?;
+                let saving = self
+                    .single
+                    .as_ref()
+                    .and_then(|single| single.total.checked_sub(combined.total));
+                if let Some(saving) = saving {
+                    writeln!(f, "saves {} over the smallest directory", saving)?;
+                }
+                Ok(())
+            }
+            None if self.optimal => writeln!(f, "no set of files frees enough"),
+            None => writeln!(f, "no set found in {} nodes", self.nodes),
+        }
+    }
+}
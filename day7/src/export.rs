@@ -0,0 +1,217 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use day7::sizes::{DenseTree, NodeId};
+
+// entries below `/` in listing order, as paths relative to it with their file size, or
+// None for directories
+fn relative_entries(tree: &DenseTree) -> Vec<(String, Option<usize>)> {
+    let mut entries = vec![];
+    let mut stack = tree
+        .children(DenseTree::ROOT)
+        .iter()
+        .rev()
+        .map(|&child| (child, tree.name(child).to_string()))
+        .collect::<Vec<_>>();
+    while let Some((node, path)) = stack.pop() {
+        for &child in tree.children(node).iter().rev() {
+            stack.push((child, format!("{}/{}", path, tree.name(child))));
+        }
+        entries.push((path, tree.file_size(node)));
+    }
+    entries
+}
+
+/// Recreates the filesystem under `root`, with every file a sparse file of its recorded
+/// size so nothing is actually written. `root` is created if needed and has to be empty.
+pub fn write_tree(tree: &DenseTree, root: &Path) -> io::Result<()> {
+    fs::create_dir_all(root)?;
+    if fs::read_dir(root)?.next().is_some() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} is not empty", root.display()),
+        ));
+    }
+    for (path, file_size) in relative_entries(tree) {
+        let target = root.join(&path);
+        match file_size {
+            None => fs::create_dir(&target)?,
+            Some(size) => File::create(&target)?.set_len(size as u64)?,
+        }
+    }
+    Ok(())
+}
+
+// field of a ustar header: octal digits followed by a NUL, failing if they do not fit
+fn octal(field: &mut [u8], value: u64) -> Result<(), String> {
+    let digits = format!("{:0width$o}", value, width = field.len() - 1);
+    if digits.len() >= field.len() {
+        return Err(format!(
+            "{} does not fit in {} octal digits",
+            value,
+            field.len() - 1
+        ));
+    }
+    field[..digits.len()].copy_from_slice(digits.as_bytes());
+    Ok(())
+}
+
+fn tar_header(path: &str, size: u64, is_directory: bool) -> io::Result<[u8; 512]> {
+    let mut header = [0u8; 512];
+    let name = if is_directory {
+        format!("{}/", path)
+    } else {
+        path.to_string()
+    };
+    // names over 100 bytes are split into a prefix and a name at a slash
+    let (prefix, name) = if name.len() <= 100 {
+        ("", name.as_str())
+    } else {
+        let split = name
+            .char_indices()
+            .filter(|&(idx, c)| c == '/' && idx <= 155 && name.len() - idx - 1 <= 100)
+            .map(|(idx, _)| idx)
+            .find(|&idx| idx + 1 < name.len())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{} is too long for a tar header", path),
+                )
+            })?;
+        (&name[..split], &name[split + 1..])
+    };
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    let mode = if is_directory { 0o755 } else { 0o644 };
+    // the other fields always fit, only the size can be too large, from 8 GiB up
+    octal(&mut header[100..108], mode).unwrap();
+    octal(&mut header[108..116], 0).unwrap();
+    octal(&mut header[116..124], 0).unwrap();
+    octal(&mut header[124..136], size).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is too large for a tar header: {}", path, err),
+        )
+    })?;
+    octal(&mut header[136..148], 0).unwrap();
+    header[156] = if is_directory { b'5' } else { b'0' };
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+
+    // the checksum is taken with its own field filled with spaces
+    header[148..156].fill(b' ');
+    let checksum = header.iter().map(|&b| b as u64).sum::<u64>();
+    // 512 bytes of at most 255 each always fit in 6 digits
+    octal(&mut header[148..155], checksum).unwrap();
+    Ok(header)
+}
+
+/// Writes the filesystem as a ustar archive at `archive`, with zero-filled files of the
+/// recorded sizes. Paths in the archive are relative to `/`.
+pub fn write_tar(tree: &DenseTree, archive: &Path) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(archive)?);
+    let zeros = [0u8; 8192];
+    for (path, file_size) in relative_entries(tree) {
+        let (size, is_directory) = match file_size {
+            None => (0, true),
+            Some(size) => (size as u64, false),
+        };
+        out.write_all(&tar_header(&path, size, is_directory)?)?;
+        // contents padded to a whole number of blocks
+        let mut left = size.div_ceil(512) * 512;
+        while left > 0 {
+            let chunk = left.min(zeros.len() as u64) as usize;
+            out.write_all(&zeros[..chunk])?;
+            left -= chunk as u64;
+        }
+    }
+    out.write_all(&[0u8; 1024])?;
+    out.flush()
+}
+
+/// Builds a tree from the real directory at `root`, which becomes `/`. Files take their
+/// apparent size; symlinks and special files are skipped. Entries are added in name order.
+pub fn read_tree(root: &Path) -> io::Result<DenseTree> {
+    let mut tree = DenseTree::new();
+    let mut stack = vec![(root.to_path_buf(), DenseTree::ROOT)];
+    while let Some((dir, dir_id)) = stack.pop() {
+        let mut entries = BTreeMap::new();
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let name = entry.file_name().into_string().map_err(|name| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{:?} is not valid UTF-8", name),
+                )
+            })?;
+            entries.insert(name, entry);
+        }
+        for (name, entry) in entries {
+            let metadata = fs::symlink_metadata(entry.path())?;
+            let file_size = if metadata.is_dir() {
+                None
+            } else if metadata.is_file() {
+                Some(metadata.len() as usize)
+            } else {
+                continue;
+            };
+            let id = tree.add(dir_id, &name, file_size);
+            if metadata.is_dir() {
+                stack.push((entry.path(), id));
+            }
+        }
+    }
+    Ok(tree)
+}
+
+/// A path whose size differs between two filesystems, `None` where it is missing.
+pub struct SizeDifference {
+    pub path: String,
+    pub expected: Option<usize>,
+    pub found: Option<usize>,
+}
+
+/// Every file and directory whose size in `found` does not match `expected`, by path.
+/// Both trees are walked together, matching entries by name.
+pub fn compare_sizes(expected: &DenseTree, found: &DenseTree) -> Vec<SizeDifference> {
+    let (expected_sizes, found_sizes) = (expected.sizes(), found.sizes());
+    let mut differences = vec![];
+    // the same path in both trees, None where it is missing
+    let mut stack: Vec<(Option<NodeId>, Option<NodeId>)> =
+        vec![(Some(DenseTree::ROOT), Some(DenseTree::ROOT))];
+    while let Some((in_expected, in_found)) = stack.pop() {
+        let expected_size = in_expected.map(|id| expected_sizes[id as usize]);
+        let found_size = in_found.map(|id| found_sizes[id as usize]);
+        if expected_size != found_size {
+            differences.push(SizeDifference {
+                path: match (in_expected, in_found) {
+                    (Some(id), _) => expected.path(id),
+                    (None, Some(id)) => found.path(id),
+                    (None, None) => unreachable!(),
+                },
+                expected: expected_size,
+                found: found_size,
+            });
+        }
+        if let Some(id) = in_expected {
+            for &child in expected.children(id) {
+                let other = in_found.and_then(|other| found.child(other, expected.name(child)));
+                stack.push((Some(child), other));
+            }
+        }
+        if let Some(id) = in_found {
+            for &child in found.children(id) {
+                let other = in_expected.and_then(|other| expected.child(other, found.name(child)));
+                if other.is_none() {
+                    stack.push((None, Some(child)));
+                }
+            }
+        }
+    }
+    differences.sort_by(|a, b| a.path.cmp(&b.path));
+    differences
+}
@@ -0,0 +1,277 @@
+use std::{collections::HashSet, fmt, str::FromStr};
+
+use common::rng::Rng;
+
+/// How file sizes are drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SizeDistribution {
+    Fixed(usize),
+    Uniform {
+        min: usize,
+        max: usize,
+    },
+    /// Uniform in the logarithm, so small files are as common as large ones per decade.
+    LogUniform {
+        min: usize,
+        max: usize,
+    },
+}
+
+impl SizeDistribution {
+    fn sample(&self, rng: &mut Rng) -> usize {
+        match *self {
+            SizeDistribution::Fixed(size) => size,
+            SizeDistribution::Uniform { min, max } => {
+                rng.range_inclusive(min as u64, max as u64) as usize
+            }
+            SizeDistribution::LogUniform { min, max } => {
+                let (low, high) = ((min.max(1) as f64).ln(), (max.max(1) as f64).ln());
+                let size = (low + rng.next_f64() * (high - low)).exp().round() as usize;
+                size.clamp(min, max)
+            }
+        }
+    }
+}
+
+/// Parses `fixed:<n>`, `uniform:<min>-<max>` or `log:<min>-<max>`.
+impl FromStr for SizeDistribution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid size distribution {:?}", s);
+        let (kind, params) = s.split_once(':').ok_or_else(invalid)?;
+        if kind == "fixed" {
+            return params
+                .parse()
+                .map(SizeDistribution::Fixed)
+                .map_err(|_| invalid());
+        }
+        let (min, max) = parse_range(params).ok_or_else(invalid)?;
+        match kind {
+            "uniform" => Ok(SizeDistribution::Uniform { min, max }),
+            "log" => Ok(SizeDistribution::LogUniform { min, max }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Parses `<min>-<max>` with `min <= max`, or a single number for both.
+pub fn parse_range(s: &str) -> Option<(usize, usize)> {
+    let (min, max) = match s.split_once('-') {
+        Some((min, max)) => (min.parse().ok()?, max.parse().ok()?),
+        None => {
+            let n = s.parse().ok()?;
+            (n, n)
+        }
+    };
+    (min <= max).then_some((min, max))
+}
+
+#[derive(Debug, Clone)]
+pub struct GeneratorConfig {
+    /// Directories this deep below `/` get no subdirectories.
+    pub max_depth: usize,
+    /// Range of the number of subdirectories in each directory.
+    pub fan_out: (usize, usize),
+    /// Range of the number of files in each directory.
+    pub files: (usize, usize),
+    pub sizes: SizeDistribution,
+    /// No more subdirectories are made once there are this many directories.
+    pub max_directories: usize,
+    pub seed: u64,
+}
+
+impl Default for GeneratorConfig {
+    /// Roughly the shape of a puzzle input.
+    fn default() -> Self {
+        Self {
+            max_depth: 8,
+            fan_out: (0, 4),
+            files: (0, 4),
+            sizes: SizeDistribution::LogUniform {
+                min: 1_000,
+                max: 350_000,
+            },
+            max_directories: 200,
+            seed: 7,
+        }
+    }
+}
+
+/// The puzzle answers and shape of a generated filesystem, computed from the generator's
+/// own tree rather than by parsing the transcript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expected {
+    pub directories: usize,
+    pub files: usize,
+    pub max_depth: usize,
+    pub total_size: usize,
+    /// Sum of the sizes of directories of at most 100000.
+    pub small_directories: usize,
+    /// Smallest directory to delete to free 30000000 of 70000000, if one exists.
+    pub smallest_to_free: Option<usize>,
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "directories {} files {} depth {} total {}",
+            self.directories, self.files, self.max_depth, self.total_size
+        )?;
+        writeln!(f, "small directories {}", self.small_directories)?;
+        match self.smallest_to_free {
+            Some(size) => writeln!(f, "smallest to free {}", size),
+            None => writeln!(f, "smallest to free none"),
+        }
+    }
+}
+
+pub struct Generated {
+    pub transcript: String,
+    pub expected: Expected,
+}
+
+struct Directory {
+    name: String,
+    depth: usize,
+    subdirectories: Vec<usize>,
+    files: Vec<(String, usize)>,
+}
+
+// a lowercase name, with an extension on some files like the puzzle input
+fn random_name(rng: &mut Rng, is_file: bool) -> String {
+    let mut name = (0..rng.range_inclusive(1, 8))
+        .map(|_| (b'a' + rng.below(26) as u8) as char)
+        .collect::<String>();
+    if is_file && rng.below(2) == 0 {
+        name.push('.');
+        name.extend((0..3).map(|_| (b'a' + rng.below(26) as u8) as char));
+    }
+    name
+}
+
+fn shuffle<T>(items: &mut [T], rng: &mut Rng) {
+    for idx in (1..items.len()).rev() {
+        items.swap(idx, rng.below(idx as u64 + 1) as usize);
+    }
+}
+
+/// Generates a random filesystem and a transcript exploring all of it with `$ cd` and
+/// `$ ls`, listing each directory once with its entries in random order. Directories are
+/// made breadth first, so `max_directories` trims the deepest levels first.
+pub fn generate(config: &GeneratorConfig) -> Generated {
+    let mut rng = Rng::new(config.seed);
+    let mut directories = vec![Directory {
+        name: "/".into(),
+        depth: 0,
+        subdirectories: vec![],
+        files: vec![],
+    }];
+    let mut next = 0;
+    while next < directories.len() {
+        let depth = directories[next].depth;
+        // names only have to be unique within their directory
+        let mut names = HashSet::new();
+        let mut unique_name = |rng: &mut Rng, is_file| loop {
+            let name = random_name(rng, is_file);
+            if names.insert(name.clone()) {
+                return name;
+            }
+        };
+        let (min_files, max_files) = config.files;
+        for _ in 0..rng.range_inclusive(min_files as u64, max_files as u64) {
+            let name = unique_name(&mut rng, true);
+            let size = config.sizes.sample(&mut rng);
+            directories[next].files.push((name, size));
+        }
+        if depth < config.max_depth {
+            let (min_fan_out, max_fan_out) = config.fan_out;
+            for _ in 0..rng.range_inclusive(min_fan_out as u64, max_fan_out as u64) {
+                if directories.len() == config.max_directories {
+                    break;
+                }
+                let name = unique_name(&mut rng, false);
+                let child = directories.len();
+                directories[next].subdirectories.push(child);
+                directories.push(Directory {
+                    name,
+                    depth: depth + 1,
+                    subdirectories: vec![],
+                    files: vec![],
+                });
+            }
+        }
+        next += 1;
+    }
+
+    // subdirectories always come after their parent, so going backwards sees them first
+    let mut sizes = vec![0; directories.len()];
+    for idx in (0..directories.len()).rev() {
+        let directory = &directories[idx];
+        sizes[idx] = directory.files.iter().map(|(_, size)| size).sum::<usize>()
+            + directory
+                .subdirectories
+                .iter()
+                .map(|&sub| sizes[sub])
+                .sum::<usize>();
+    }
+    let total_size = sizes[0];
+    let expected = Expected {
+        directories: directories.len(),
+        files: directories.iter().map(|dir| dir.files.len()).sum(),
+        max_depth: directories.iter().map(|dir| dir.depth).max().unwrap(),
+        total_size,
+        small_directories: sizes.iter().filter(|&&size| size <= 100_000).sum(),
+        smallest_to_free: (total_size <= 70_000_000)
+            .then(|| {
+                let needed = (total_size + 30_000_000).saturating_sub(70_000_000);
+                sizes.iter().copied().filter(|&size| size >= needed).min()
+            })
+            .flatten(),
+    };
+
+    let mut transcript = String::from("$ cd /\n");
+    enum Step {
+        Enter(usize),
+        Leave,
+    }
+    let mut steps = vec![Step::Enter(0)];
+    while let Some(step) = steps.pop() {
+        let idx = match step {
+            Step::Leave => {
+                transcript.push_str("$ cd ..\n");
+                continue;
+            }
+            Step::Enter(idx) => idx,
+        };
+        let directory = &directories[idx];
+        if idx != 0 {
+            transcript.push_str(&format!("$ cd {}\n", directory.name));
+        }
+        transcript.push_str("$ ls\n");
+        let mut listing = directory
+            .subdirectories
+            .iter()
+            .map(|&sub| format!("dir {}\n", directories[sub].name))
+            .chain(
+                directory
+                    .files
+                    .iter()
+                    .map(|(name, size)| format!("{} {}\n", size, name)),
+            )
+            .collect::<Vec<_>>();
+        shuffle(&mut listing, &mut rng);
+        transcript.extend(listing);
+        let mut visit = directory.subdirectories.clone();
+        shuffle(&mut visit, &mut rng);
+        for &sub in visit.iter().rev() {
+            steps.push(Step::Leave);
+            steps.push(Step::Enter(sub));
+        }
+    }
+    Generated {
+        transcript,
+        expected,
+    }
+}
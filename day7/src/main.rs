@@ -1,11 +1,25 @@
 use std::{
-    collections::{HashMap, VecDeque},
-    fs,
-    hash::Hash,
+    env, fs,
+    io::{self, IsTerminal},
+    path::Path,
+    process,
     str::FromStr,
-    string::ParseError,
 };
 
+use day7::{
+    generate::{self, Expected, GeneratorConfig},
+    sizes::{DenseTree, NodeId},
+};
+use report::{Du, EntryKind, Largest, Report, Tree};
+use shell::Shell;
+use vfs::{TranscriptError, Vfs, VfsError};
+
+mod cleanup;
+mod export;
+mod report;
+mod shell;
+mod vfs;
+
 #[derive(Debug)]
 struct DirListing {
     name: String,
@@ -19,268 +33,290 @@ enum DirEntry {
 }
 
 impl FromStr for DirListing {
-    type Err = ParseError;
+    type Err = VfsError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.starts_with("dir") {
-            return Ok(DirListing {
-                name: s.split(" ").nth(1).unwrap().into(),
-                dir_entry: DirEntry::DIRECTORY,
-            });
-        } else {
-            let mut split_line = s.split(" ");
-            let file_size = split_line.next().unwrap();
-            let file_name = split_line.next().unwrap();
-            return Ok(DirListing {
-                name: file_name.into(),
-                dir_entry: DirEntry::FILE(file_size.parse::<usize>().unwrap()),
-            });
+        let invalid = || VfsError::InvalidListing(s.into());
+        let mut split_line = s.split(' ');
+        let (kind, name) = match (split_line.next(), split_line.next(), split_line.next()) {
+            (Some(kind), Some(name), None) => (kind, name),
+            _ => return Err(invalid()),
+        };
+        if name.is_empty() || name.contains('/') || name == "." || name == ".." {
+            return Err(invalid());
         }
+        let dir_entry = if kind == "dir" {
+            DirEntry::DIRECTORY
+        } else {
+            DirEntry::FILE(kind.parse::<usize>().map_err(|_| invalid())?)
+        };
+        Ok(DirListing {
+            name: name.into(),
+            dir_entry,
+        })
     }
 }
 
-fn concat_path(dirname: &str, basename: &str) -> String {
-    format!("{}/{}", dirname, basename).replace("//", "/")
+fn arg_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    let position = args.iter().position(|arg| arg == name)?;
+    args.get(position + 1).map(String::as_str)
 }
 
-impl FromStr for Arena<String, DirListing> {
-    type Err = ParseError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut lines = s.lines().peekable();
-        let mut arena = Arena::<String, _>::new();
-
-        // add the root node
-        arena.add_node(
-            "/".into(),
-            DirListing {
-                name: "/".into(),
-                dir_entry: DirEntry::DIRECTORY,
-            },
-            None,
-        );
-        let mut curr_dir_name: String = "".into();
-        while let Some(line) = lines.next() {
-            // we always reach here with the next line being a command
-            if line.starts_with("$ cd") {
-                let name = line.split(" ").nth(2).unwrap();
-                // let tmp_curr_dir_name = &curr_dir_name;
-                if name == ".." {
-                    curr_dir_name = arena
-                        .nodes
-                        .get(&curr_dir_name)
-                        .unwrap()
-                        .parent
-                        .clone()
-                        .unwrap();
-                } else {
-                    curr_dir_name = concat_path(&curr_dir_name, name);
-                }
-            } else {
-                // $ ls
-                while let Some(next_line) = lines.peek() {
-                    // break from the loop if the next line is a command
-                    if next_line.starts_with("$") {
-                        break;
-                    }
-
-                    let dir_listing: DirListing = lines.next().unwrap().parse().unwrap();
-                    arena.add_node(
-                        concat_path(&curr_dir_name, &dir_listing.name),
-                        dir_listing,
-                        Some(curr_dir_name.clone()),
-                    );
-                }
-            }
-        }
-
-        Ok(arena)
-    }
+fn invalid(name: &str, value: &str) -> ! {
+    eprintln!("invalid {} {:?}", name, value);
+    process::exit(1);
 }
 
-#[derive(Debug)]
-pub struct TreeNode<ID, T>
-where
-    ID: Hash + Eq + PartialEq + Clone,
-{
-    id: ID,
-    data: T,
-    parent: Option<ID>,
-    children: Vec<ID>,
+// the value given for a flag, which must be present once the flag is
+fn flag_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .any(|arg| arg == name)
+        .then(|| arg_value(args, name).unwrap_or_else(|| invalid(name, "")))
 }
 
-impl<ID, T> TreeNode<ID, T>
-where
-    ID: Hash + Eq + PartialEq + Clone,
-{
-    pub fn new(id: ID, data: T, parent: Option<ID>) -> Self {
-        Self {
-            id,
-            data,
-            parent,
-            children: vec![],
-        }
-    }
+fn number_arg<T: FromStr>(args: &[String], name: &str) -> Option<T> {
+    flag_value(args, name).map(|value| value.parse().unwrap_or_else(|_| invalid(name, value)))
+}
 
-    pub fn add_child(&mut self, child_id: ID) {
-        self.children.push(child_id);
-    }
+// the transcript in input/day7.txt, or another one with --input <file>
+fn read_transcript(args: &[String]) -> Vfs {
+    let path = arg_value(args, "--input").unwrap_or("input/day7.txt");
+    let contents = fs::read_to_string(path).unwrap();
+    contents.parse().unwrap_or_else(|err: TranscriptError| {
+        eprintln!("{}: {}", path, err);
+        process::exit(1);
+    })
+}
 
-    pub fn remove_child(&mut self, child_id: ID) {
-        self.children
-            .remove(self.children.iter().position(|x| x == &child_id).unwrap());
+fn generator_config(args: &[String]) -> GeneratorConfig {
+    let mut config = GeneratorConfig::default();
+    let range = |name: &str| {
+        flag_value(args, name)
+            .map(|value| generate::parse_range(value).unwrap_or_else(|| invalid(name, value)))
+    };
+    config.max_depth = number_arg(args, "--depth").unwrap_or(config.max_depth);
+    config.max_directories =
+        number_arg(args, "--max-directories").unwrap_or(config.max_directories);
+    config.fan_out = range("--fan-out").unwrap_or(config.fan_out);
+    config.files = range("--files").unwrap_or(config.files);
+    config.seed = number_arg(args, "--seed").unwrap_or(config.seed);
+    if let Some(sizes) = flag_value(args, "--sizes") {
+        config.sizes = sizes.parse().unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            process::exit(1);
+        });
     }
+    config
 }
 
-impl Arena<String, DirListing> {
-    fn size(&self, node_id: &String, size_memo: &mut HashMap<String, usize>) -> usize {
-        let node = self.nodes.get(node_id).unwrap();
-        if let Some(size_val) = size_memo.get(node_id) {
-            return *size_val;
+/// The same answers [`generate::generate`] works out, from the parsed filesystem.
+fn measure(tree: &DenseTree) -> Expected {
+    let sizes = tree.sizes();
+    let total_size = sizes[DenseTree::ROOT as usize];
+    let mut directory_sizes = vec![];
+    let mut files = 0;
+    let mut max_depth = 0;
+    // parents come before their children, so a parent's depth is known by then
+    let mut depths = vec![0; tree.len()];
+    for id in 0..tree.len() {
+        if let Some(parent) = tree.parent(id as NodeId) {
+            depths[id] = depths[parent as usize] + 1;
         }
-
-        let size = match node.data.dir_entry {
-            DirEntry::FILE(file_size) => file_size,
-            DirEntry::DIRECTORY => {
-                let dir_size = node
-                    .children
+        if tree.is_directory(id as NodeId) {
+            directory_sizes.push(sizes[id]);
+            max_depth = max_depth.max(depths[id]);
+        } else {
+            files += 1;
+        }
+    }
+    let needed = (total_size + 30_000_000).saturating_sub(70_000_000);
+    Expected {
+        directories: directory_sizes.len(),
+        files,
+        max_depth,
+        total_size,
+        small_directories: directory_sizes
+            .iter()
+            .filter(|&&size| size <= 100_000)
+            .sum(),
+        smallest_to_free: (total_size <= 70_000_000)
+            .then(|| {
+                directory_sizes
                     .iter()
-                    .map(|child| self.size(child, size_memo))
-                    .sum();
-                size_memo.insert(node_id.into(), dir_size);
-                dir_size
-            }
-        };
-        return size;
+                    .copied()
+                    .filter(|&size| size >= needed)
+                    .min()
+            })
+            .flatten(),
     }
 }
 
-pub struct Arena<ID, T>
-where
-    ID: Hash + Eq + PartialEq + Clone,
-{
-    nodes: HashMap<ID, TreeNode<ID, T>>,
-}
-
-pub struct DfsIterator<'a, ID, T>
-where
-    ID: Hash + Eq + PartialEq + Clone,
-{
-    arena: &'a Arena<ID, T>,
-    stack: VecDeque<ID>,
+// the filesystem from the transcript, or from a real directory with --import <dir>
+fn load_tree(args: &[String]) -> DenseTree {
+    match arg_value(args, "--import") {
+        Some(dir) => export::read_tree(Path::new(dir)).unwrap_or_else(|err| {
+            eprintln!("{}: {}", dir, err);
+            process::exit(1);
+        }),
+        None => read_transcript(args).tree,
+    }
 }
 
-impl<'a, ID, T> Iterator for DfsIterator<'a, ID, T>
-where
-    ID: Hash + Eq + PartialEq + Clone,
-{
-    type Item = ID;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some(curr_index) = self.stack.pop_front() {
-            // get the children of the current node, and push them so the left-most child is added last, to be popped-off first
-            let children = &self.arena.nodes.get(&curr_index).unwrap().children;
-            for child in children.iter().rev() {
-                self.stack.push_front(child.clone())
+fn main() {
+    // day7 shell: explore the filesystem, reading commands from stdin
+    // day7 export <dir> | tar <file>: write the filesystem out with files of the right sizes
+    // day7 compare <dir>: check a real directory against the transcript's sizes
+    // day7 generate | verify [--depth <n>] [--fan-out <min>-<max>] [--files <min>-<max>]
+    //      [--sizes fixed:<n>|uniform:<min>-<max>|log:<min>-<max>] [--max-directories <n>]
+    //      [--seed <n>]: print a random transcript, or check it parses to the right answers
+    // any of these read --input <file> instead of input/day7.txt
+    // day7 [--import <dir>] [--disk-size <n>] [--required-free <n>] [--max-nodes <n>]
+    // day7 [--import <dir>] --du | --tree | --largest <n> [--files] [--max-depth <n>]
+    //      [--human] [--json]
+    let args = env::args().collect::<Vec<_>>();
+    match (args.get(1).map(String::as_str), args.get(2)) {
+        (Some("export"), Some(dir)) => {
+            if let Err(err) = export::write_tree(&read_transcript(&args).tree, Path::new(dir)) {
+                eprintln!("{}: {}", dir, err);
+                process::exit(1);
             }
-
-            return Some(curr_index);
+            return;
         }
-        return None;
-    }
-}
-
-impl<ID, T> Arena<ID, T>
-where
-    ID: Hash + Eq + PartialEq + Clone + std::fmt::Debug,
-{
-    pub fn add_node(&mut self, key: ID, data: T, parent_key: Option<ID>) -> &TreeNode<ID, T> {
-        // add the element to the arena
-        self.nodes.insert(
-            key.clone(),
-            TreeNode::new(key.clone(), data, parent_key.clone()),
-        );
-
-        // properly associate it to the parent
-        if let Some(parent_key_val) = parent_key {
-            let parent_node = self.nodes.get_mut(&parent_key_val).unwrap();
-            parent_node.add_child(key.clone());
+        (Some("tar"), Some(file)) => {
+            if let Err(err) = export::write_tar(&read_transcript(&args).tree, Path::new(file)) {
+                eprintln!("{}: {}", file, err);
+                process::exit(1);
+            }
+            return;
         }
-
-        return self.nodes.get(&key).unwrap();
-    }
-
-    pub fn remove_node(&mut self, key: ID) -> Option<TreeNode<ID, T>> {
-        // if the node to remove has a parent, remove this node from the parent's children
-        if let Some(parent_id) = self.nodes.get(&key).unwrap().parent.clone() {
-            self.nodes
-                .get_mut(&parent_id)
-                .unwrap()
-                .remove_child(key.clone());
+        (Some("compare"), Some(dir)) => {
+            let found = export::read_tree(Path::new(dir)).unwrap_or_else(|err| {
+                eprintln!("{}: {}", dir, err);
+                process::exit(1);
+            });
+            let expected = read_transcript(&args).tree;
+            let differences = export::compare_sizes(&expected, &found);
+            for difference in &differences {
+                let show = |size: Option<usize>| size.map_or("missing".into(), |s| s.to_string());
+                println!(
+                    "{}: expected {} found {}",
+                    difference.path,
+                    show(difference.expected),
+                    show(difference.found)
+                );
+            }
+            println!("{} differences", differences.len());
+            if !differences.is_empty() {
+                process::exit(1);
+            }
+            return;
         }
-
-        // remove the node
-        self.nodes.remove(&key)
+        _ => {}
     }
-
-    pub fn get(&self, key: &ID) -> Option<&TreeNode<ID, T>> {
-        return self.nodes.get(key);
+    if matches!(args.get(1).map(String::as_str), Some("generate" | "verify")) {
+        let generated = generate::generate(&generator_config(&args));
+        if args[1] == "generate" {
+            print!("{}", generated.transcript);
+            eprint!("{}", generated.expected);
+            return;
+        }
+        let vfs: Vfs = generated.transcript.parse().unwrap_or_else(|err| {
+            eprintln!("generated transcript {}", err);
+            process::exit(1);
+        });
+        let found = measure(&vfs.tree);
+        print!("expected\n{}found\n{}", generated.expected, found);
+        if found != generated.expected {
+            println!("mismatch");
+            process::exit(1);
+        }
+        return;
     }
-
-    pub fn get_mut(&mut self, key: &ID) -> Option<&mut TreeNode<ID, T>> {
-        return self.nodes.get_mut(key);
+    if args.get(1).map(String::as_str) == Some("shell") {
+        let vfs = read_transcript(&args);
+        let stdin = io::stdin();
+        let interactive = stdin.is_terminal();
+        Shell::new(vfs)
+            .run(stdin.lock(), io::stdout().lock(), interactive)
+            .unwrap();
+        return;
     }
-
-    pub fn new() -> Self {
-        return Self {
-            nodes: HashMap::new(),
+    let max_depth = number_arg(&args, "--max-depth");
+    let largest = number_arg(&args, "--largest");
+    let has_flag = |name: &str| args.iter().any(|arg| arg == name);
+    if has_flag("--du") || has_flag("--tree") || largest.is_some() {
+        let tree = load_tree(&args);
+        let sizes = tree.sizes();
+        let report: Box<dyn Report> = if let Some(n) = largest {
+            let kind = if has_flag("--files") {
+                EntryKind::Files
+            } else {
+                EntryKind::Directories
+            };
+            Box::new(Largest::new(&tree, DenseTree::ROOT, n, kind, &sizes))
+        } else if has_flag("--tree") {
+            Box::new(Tree::new(&tree, DenseTree::ROOT, max_depth, &sizes))
+        } else {
+            Box::new(Du::new(&tree, DenseTree::ROOT, max_depth, &sizes))
         };
+        if has_flag("--json") {
+            println!("{}", report.json());
+        } else {
+            print!("{}", report.text(has_flag("--human")));
+        }
+        return;
     }
 
-    pub fn dfs(&self, start_node: ID) -> DfsIterator<ID, T> {
-        //doing a pre-order walk of the tree, so we yield each node as we visit it, starting with the start_node
-        DfsIterator {
-            arena: self,
-            stack: vec![start_node].into(),
+    let tree = load_tree(&args);
+    let disk_size = number_arg(&args, "--disk-size").unwrap_or(70_000_000);
+    let required_free = number_arg(&args, "--required-free").unwrap_or(30_000_000);
+    let max_nodes = number_arg(&args, "--max-nodes").unwrap_or(1_000_000);
+    match cleanup::plan(&tree, disk_size, required_free, max_nodes) {
+        Ok(plan) => print!("{}", plan),
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
         }
     }
 }
 
-fn main() {
-    let mut tree = Arena::<usize, String>::new();
-    tree.add_node(1, "foo".to_owned(), None);
-    tree.add_node(2, "bar".to_owned(), Some(1));
-    tree.add_node(82, "baz".to_owned(), Some(1));
-    tree.add_node(42, "bam".to_owned(), Some(2));
-    tree.add_node(92, "boom".to_owned(), Some(1));
-
-    // tree is expected to be:
-    //          1
-    //       2     82   92
-    //      42
-
-    for node_key in tree.dfs(1) {
-        println!("{}", node_key);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // both parsers rebuild the filesystem the generator had in mind
+    fn round_trip(config: GeneratorConfig) {
+        let generated = generate::generate(&config);
+        let vfs: Vfs = generated.transcript.parse().unwrap();
+        assert_eq!(measure(&vfs.tree), generated.expected);
+        let tree: DenseTree = generated.transcript.parse().unwrap();
+        assert_eq!(measure(&tree), generated.expected);
     }
-    let contents = fs::read_to_string("input/day7.txt").unwrap();
-    let tree_new: Arena<String, DirListing> = contents.parse().unwrap();
-    for node_key in tree_new.dfs("/".into()) {
-        println!("{}", node_key);
+
+    #[test]
+    fn default_shape() {
+        round_trip(GeneratorConfig::default());
     }
 
-    let mut size_memo = HashMap::new();
-    tree_new.size(&"/".into(), &mut size_memo);
-    let total_used = size_memo.get("/").unwrap();
-    let total_free = 70000000 - total_used;
-    let needed = 30000000 - total_free;
-    dbg!(total_free, &needed);
+    #[test]
+    fn wide_tree() {
+        round_trip(GeneratorConfig {
+            max_depth: 2,
+            fan_out: (50, 80),
+            max_directories: 5_000,
+            ..GeneratorConfig::default()
+        });
+    }
 
-    //dbg!(&size_memo);
-    let mut res = size_memo
-        .iter()
-        .map(|(_, size)| size)
-        .filter(|&size| size >= &needed)
-        .collect::<Vec<_>>();
-    res.sort();
-    dbg!(res);
+    #[test]
+    fn deep_chain() {
+        let config = GeneratorConfig {
+            max_depth: 10_000,
+            fan_out: (1, 1),
+            max_directories: 10_001,
+            ..GeneratorConfig::default()
+        };
+        assert_eq!(generate::generate(&config).expected.max_depth, 10_000);
+        round_trip(config);
+    }
 }
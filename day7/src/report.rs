@@ -0,0 +1,273 @@
+use std::cmp::Reverse;
+
+use day7::sizes::{DenseTree, NodeId};
+
+/// A report over part of the filesystem, rendered as text for people or JSON for tools.
+/// Sizes in JSON are always in bytes.
+pub trait Report {
+    fn text(&self, human: bool) -> String;
+    fn json(&self) -> String;
+}
+
+/// Formats `size` in bytes with a K/M/G/T suffix in powers of 1024, like `du -h`.
+pub fn human_size(size: usize) -> String {
+    const UNITS: [&str; 4] = ["K", "M", "G", "T"];
+    if size < 1024 {
+        return size.to_string();
+    }
+    let mut value = size as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if value < 10.0 {
+        format!("{:.1}{}", value, UNITS[unit])
+    } else {
+        format!("{:.0}{}", value, UNITS[unit])
+    }
+}
+
+fn format_size(size: usize, human: bool) -> String {
+    if human {
+        human_size(size)
+    } else {
+        size.to_string()
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/// Pre-order walk from `root` with each node's depth below it, not descending past
+/// `max_depth`.
+fn walk(tree: &DenseTree, root: NodeId, max_depth: Option<usize>) -> Vec<(NodeId, usize)> {
+    let mut nodes = vec![];
+    let mut stack = vec![(root, 0usize)];
+    while let Some((node, depth)) = stack.pop() {
+        if max_depth.is_none_or(|max_depth| depth < max_depth) {
+            for &child in tree.children(node).iter().rev() {
+                stack.push((child, depth + 1));
+            }
+        }
+        nodes.push((node, depth));
+    }
+    nodes
+}
+
+/// Size of every directory below `root`, deepest first like `du`.
+pub struct Du {
+    pub entries: Vec<(String, usize)>,
+}
+
+impl Du {
+    /// Lists directories at most `max_depth` levels below `root`; their sizes still
+    /// include everything underneath. `sizes` is indexed by node id.
+    pub fn new(tree: &DenseTree, root: NodeId, max_depth: Option<usize>, sizes: &[usize]) -> Self {
+        let mut entries = walk(tree, root, max_depth)
+            .into_iter()
+            .filter(|&(node, _)| tree.is_directory(node))
+            .map(|(node, _)| (tree.path(node), sizes[node as usize]))
+            .collect::<Vec<_>>();
+        entries.reverse();
+        Self { entries }
+    }
+}
+
+impl Report for Du {
+    fn text(&self, human: bool) -> String {
+        self.entries
+            .iter()
+            .map(|(path, size)| format!("{}\t{}\n", format_size(*size, human), path))
+            .collect()
+    }
+
+    fn json(&self) -> String {
+        let entries = self
+            .entries
+            .iter()
+            .map(|(path, size)| format!("{{\"path\":{},\"size\":{}}}", json_string(path), size))
+            .collect::<Vec<_>>();
+        format!("[{}]", entries.join(","))
+    }
+}
+
+pub struct TreeLine {
+    pub depth: usize,
+    pub name: String,
+    pub size: usize,
+    pub is_directory: bool,
+    /// A directory with entries below `max_depth`, which are left out.
+    pub truncated: bool,
+}
+
+/// An indented view of everything below a directory, in listing order.
+pub struct Tree {
+    pub lines: Vec<TreeLine>,
+}
+
+impl Tree {
+    pub fn new(tree: &DenseTree, root: NodeId, max_depth: Option<usize>, sizes: &[usize]) -> Self {
+        let lines = walk(tree, root, max_depth)
+            .into_iter()
+            .map(|(node, depth)| TreeLine {
+                depth,
+                name: tree.name(node).to_string(),
+                size: sizes[node as usize],
+                is_directory: tree.is_directory(node),
+                truncated: max_depth == Some(depth) && !tree.children(node).is_empty(),
+            })
+            .collect();
+        Self { lines }
+    }
+}
+
+impl Report for Tree {
+    fn text(&self, human: bool) -> String {
+        let mut text = String::new();
+        for line in &self.lines {
+            let name = line.name.trim_end_matches('/');
+            let suffix = if line.is_directory { "/" } else { "" };
+            text += &format!(
+                "{}{}{} ({})\n",
+                "  ".repeat(line.depth),
+                name,
+                suffix,
+                format_size(line.size, human)
+            );
+        }
+        text
+    }
+
+    /// Nested objects, with a `children` array on every directory. Directories cut off by
+    /// `max_depth` have `"truncated":true` instead, so they do not read as empty.
+    fn json(&self) -> String {
+        let mut json = String::new();
+        // depths of the directories whose children array is still open
+        let mut open = Vec::<usize>::new();
+        for line in &self.lines {
+            while open.last().is_some_and(|&depth| depth >= line.depth) {
+                open.pop();
+                json += "]}";
+            }
+            if !json.is_empty() && !json.ends_with('[') {
+                json.push(',');
+            }
+            let kind = if line.is_directory {
+                "directory"
+            } else {
+                "file"
+            };
+            json += &format!(
+                "{{\"name\":{},\"type\":\"{}\",\"size\":{}",
+                json_string(&line.name),
+                kind,
+                line.size
+            );
+            if line.truncated {
+                json += ",\"truncated\":true}";
+            } else if line.is_directory {
+                json += ",\"children\":[";
+                open.push(line.depth);
+            } else {
+                json.push('}');
+            }
+        }
+        for _ in open {
+            json += "]}";
+        }
+        json
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EntryKind {
+    Directories,
+    Files,
+}
+
+/// The `n` largest directories or files below a directory, biggest first.
+pub struct Largest {
+    pub kind: EntryKind,
+    pub entries: Vec<(String, usize)>,
+}
+
+impl Largest {
+    /// Ties are broken by path so the table is stable. `root` itself is left out. Only
+    /// the paths in the table are spelled out.
+    pub fn new(tree: &DenseTree, root: NodeId, n: usize, kind: EntryKind, sizes: &[usize]) -> Self {
+        let path_order = tree.path_order();
+        let mut nodes = tree
+            .pre_order(root)
+            .skip(1)
+            .filter(|&node| tree.is_directory(node) == (kind == EntryKind::Directories))
+            .collect::<Vec<_>>();
+        nodes.sort_by_key(|&node| (Reverse(sizes[node as usize]), path_order[node as usize]));
+        let entries = nodes
+            .into_iter()
+            .take(n)
+            .map(|node| (tree.path(node), sizes[node as usize]))
+            .collect();
+        Self { kind, entries }
+    }
+}
+
+impl Report for Largest {
+    fn text(&self, human: bool) -> String {
+        let sizes = self
+            .entries
+            .iter()
+            .map(|(_, size)| format_size(*size, human))
+            .collect::<Vec<_>>();
+        let width = sizes.iter().map(String::len).max().unwrap_or(0).max(4);
+        let header = match self.kind {
+            EntryKind::Directories => "directory",
+            EntryKind::Files => "file",
+        };
+        let mut text = format!("rank  {:>width$}  {}\n", "size", header, width = width);
+        for (rank, ((path, _), size)) in self.entries.iter().zip(&sizes).enumerate() {
+            text += &format!(
+                "{:>4}  {:>width$}  {}\n",
+                rank + 1,
+                size,
+                path,
+                width = width
+            );
+        }
+        text
+    }
+
+    fn json(&self) -> String {
+        let kind = match self.kind {
+            EntryKind::Directories => "directory",
+            EntryKind::Files => "file",
+        };
+        let entries = self
+            .entries
+            .iter()
+            .map(|(path, size)| {
+                format!(
+                    "{{\"path\":{},\"type\":\"{}\",\"size\":{}}}",
+                    json_string(path),
+                    kind,
+                    size
+                )
+            })
+            .collect::<Vec<_>>();
+        format!("[{}]", entries.join(","))
+    }
+}
@@ -0,0 +1,177 @@
+use std::io::{self, BufRead, Write};
+
+use crate::{
+    report::{Du, Report, Tree},
+    vfs::Vfs,
+};
+
+const HELP: &str = "\
+commands:
+  cd [dir]                 change directory, to / by default
+  ls [dir]                 list a directory
+  pwd                      print the current directory
+  du [-s] [-h] [-d N] [dir] size of every directory below dir, or only dir with -s
+  find [dir] -size [+-]N   files larger (+), smaller (-) or exactly N bytes
+  tree [-h] [-d N] [dir]   indented tree with sizes
+  help                     show this help
+  exit                     leave the shell";
+
+/// A small shell over a reconstructed filesystem. The size of every node is worked out
+/// once when the session starts, since nothing in it can change the filesystem.
+pub struct Shell {
+    vfs: Vfs,
+    // indexed by node id
+    sizes: Vec<usize>,
+}
+
+enum SizeFilter {
+    Above(usize),
+    Below(usize),
+    Exactly(usize),
+}
+
+impl SizeFilter {
+    fn parse(s: &str) -> Option<Self> {
+        // a trailing `c` means bytes in find, which is the only unit here anyway
+        let s = s.strip_suffix('c').unwrap_or(s);
+        if let Some(size) = s.strip_prefix('+') {
+            return size.parse().ok().map(SizeFilter::Above);
+        }
+        if let Some(size) = s.strip_prefix('-') {
+            return size.parse().ok().map(SizeFilter::Below);
+        }
+        s.parse().ok().map(SizeFilter::Exactly)
+    }
+
+    fn matches(&self, size: usize) -> bool {
+        match self {
+            SizeFilter::Above(limit) => size > *limit,
+            SizeFilter::Below(limit) => size < *limit,
+            SizeFilter::Exactly(limit) => size == *limit,
+        }
+    }
+}
+
+impl Shell {
+    /// Starts a session in the root directory.
+    pub fn new(mut vfs: Vfs) -> Self {
+        vfs.cd("/").unwrap();
+        Self {
+            sizes: vfs.tree.sizes(),
+            vfs,
+        }
+    }
+
+    /// Reads commands from `input` until it ends or `exit` is entered, writing their
+    /// output to `output`. A prompt is only shown when `interactive`.
+    pub fn run<R, W>(&mut self, input: R, mut output: W, interactive: bool) -> io::Result<()>
+    where
+        R: BufRead,
+        W: Write,
+    {
+        let mut lines = input.lines();
+        loop {
+            if interactive {
+                write!(output, "{}$ ", self.vfs.cwd())?;
+                output.flush()?;
+            }
+            let Some(line) = lines.next() else {
+                break;
+            };
+            let line = line?;
+            let args = line.split_whitespace().collect::<Vec<_>>();
+            if matches!(args.first(), Some(&"exit") | Some(&"quit")) {
+                break;
+            }
+            match self.execute(&args) {
+                Ok(text) => write!(output, "{}", text)?,
+                Err(err) => writeln!(output, "{}: {}", args[0], err)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs a single command, returning its output.
+    pub fn execute(&mut self, args: &[&str]) -> Result<String, String> {
+        let Some((&command, args)) = args.split_first() else {
+            return Ok(String::new());
+        };
+        match command {
+            "cd" => {
+                self.vfs
+                    .cd(args.first().copied().unwrap_or("/"))
+                    .map_err(|err| err.to_string())?;
+                Ok(String::new())
+            }
+            "pwd" => Ok(format!("{}\n", self.vfs.cwd())),
+            "ls" => self.ls(args.first().copied().unwrap_or(".")),
+            "du" | "tree" => self.report(command, args),
+            "find" => self.find(args),
+            "help" => Ok(format!("{}\n", HELP)),
+            _ => Err("unknown command, try help".into()),
+        }
+    }
+
+    fn ls(&self, path: &str) -> Result<String, String> {
+        let dir = self.vfs.directory(path).map_err(|err| err.to_string())?;
+        let mut text = String::new();
+        let tree = &self.vfs.tree;
+        for &child in tree.children(dir) {
+            match tree.file_size(child) {
+                None => text += &format!("dir {}\n", tree.name(child)),
+                Some(size) => text += &format!("{} {}\n", size, tree.name(child)),
+            }
+        }
+        Ok(text)
+    }
+
+    /// `du` and `tree`, which share their `-h` and `-d` options.
+    fn report(&mut self, command: &str, args: &[&str]) -> Result<String, String> {
+        let mut human = false;
+        let mut max_depth = None;
+        let mut path = ".";
+        let mut args = args.iter();
+        while let Some(&arg) = args.next() {
+            match arg {
+                "-h" => human = true,
+                "-s" if command == "du" => max_depth = Some(0),
+                "-d" => {
+                    let depth = args.next().and_then(|depth| depth.parse().ok());
+                    max_depth = Some(depth.ok_or("-d needs a depth")?);
+                }
+                _ if arg.starts_with('-') => return Err(format!("unknown option {}", arg)),
+                _ => path = arg,
+            }
+        }
+        let dir = self.vfs.directory(path).map_err(|err| err.to_string())?;
+        let tree = &self.vfs.tree;
+        Ok(if command == "du" {
+            Du::new(tree, dir, max_depth, &self.sizes).text(human)
+        } else {
+            Tree::new(tree, dir, max_depth, &self.sizes).text(human)
+        })
+    }
+
+    fn find(&mut self, args: &[&str]) -> Result<String, String> {
+        let (path, rest) = match args.first() {
+            Some(path) if !path.starts_with('-') => (*path, &args[1..]),
+            _ => (".", args),
+        };
+        let filter = match rest {
+            [] => None,
+            ["-size", size] => Some(SizeFilter::parse(size).ok_or("invalid size")?),
+            _ => return Err("usage: find [dir] -size [+-]N".into()),
+        };
+        let dir = self.vfs.directory(path).map_err(|err| err.to_string())?;
+        let mut text = String::new();
+        let tree = &self.vfs.tree;
+        for node in tree.pre_order(dir) {
+            if let Some(size) = tree.file_size(node) {
+                if filter.as_ref().is_none_or(|filter| filter.matches(size)) {
+                    text += &format!("{}\n", tree.path(node));
+                }
+            }
+        }
+        Ok(text)
+    }
+}
@@ -0,0 +1,258 @@
+use std::{collections::HashMap, str::FromStr};
+
+pub type NodeId = u32;
+
+/// Hands out dense ids for strings, in the order they are first seen.
+#[derive(Debug, Default)]
+pub struct Interner {
+    ids: HashMap<String, u32>,
+    strings: Vec<String>,
+}
+
+impl Interner {
+    pub fn intern(&mut self, s: &str) -> u32 {
+        if let Some(&id) = self.ids.get(s) {
+            return id;
+        }
+        let id = u32::try_from(self.strings.len()).expect("too many strings to intern");
+        self.ids.insert(s.to_string(), id);
+        self.strings.push(s.to_string());
+        id
+    }
+
+    pub fn get(&self, s: &str) -> Option<u32> {
+        self.ids.get(s).copied()
+    }
+
+    pub fn resolve(&self, id: u32) -> &str {
+        &self.strings[id as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+/// A filesystem in flat vectors indexed by node id, with `/` as node 0.
+///
+/// A node is identified by its parent's id and its interned name rather than by its full
+/// path, so memory stays linear in the number of nodes however deep the tree is. Nodes
+/// are only added under a directory that already exists, so every node's id is greater
+/// than its parent's.
+#[derive(Debug)]
+pub struct DenseTree {
+    names: Interner,
+    name: Vec<u32>,
+    parent: Vec<NodeId>,
+    // None for directories
+    file_size: Vec<Option<usize>>,
+    // in the order they were added
+    children: Vec<Vec<NodeId>>,
+    by_name: HashMap<(NodeId, u32), NodeId>,
+}
+
+impl Default for DenseTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DenseTree {
+    pub const ROOT: NodeId = 0;
+
+    pub fn new() -> Self {
+        let mut names = Interner::default();
+        let root_name = names.intern("/");
+        Self {
+            names,
+            name: vec![root_name],
+            parent: vec![Self::ROOT],
+            file_size: vec![None],
+            children: vec![vec![]],
+            by_name: HashMap::new(),
+        }
+    }
+
+    /// Adds a file of `file_size`, or a directory when `None`, named `name` in the
+    /// directory `parent`. Adding a name again returns the id it already has.
+    pub fn add(&mut self, parent: NodeId, name: &str, file_size: Option<usize>) -> NodeId {
+        assert!(self.is_directory(parent), "parent has to be a directory");
+        let name = self.names.intern(name);
+        let next = NodeId::try_from(self.parent.len()).expect("too many nodes");
+        let id = *self.by_name.entry((parent, name)).or_insert(next);
+        if id == next {
+            self.name.push(name);
+            self.parent.push(parent);
+            self.file_size.push(file_size);
+            self.children.push(vec![]);
+            self.children[parent as usize].push(id);
+        }
+        id
+    }
+
+    /// Changes the size of the file `id`.
+    pub fn set_file_size(&mut self, id: NodeId, size: usize) {
+        assert!(
+            !self.is_directory(id),
+            "only files have a size of their own"
+        );
+        self.file_size[id as usize] = Some(size);
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    pub fn child(&self, parent: NodeId, name: &str) -> Option<NodeId> {
+        let name = self.names.get(name)?;
+        self.by_name.get(&(parent, name)).copied()
+    }
+
+    /// The entries of the directory `id`, in the order they were added.
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        &self.children[id as usize]
+    }
+
+    /// `root` and everything below it, each node before its children.
+    pub fn pre_order(&self, root: NodeId) -> PreOrder<'_> {
+        PreOrder {
+            tree: self,
+            stack: vec![root],
+        }
+    }
+
+    /// The node at the absolute `path`.
+    pub fn id(&self, path: &str) -> Option<NodeId> {
+        path.split('/')
+            .filter(|component| !component.is_empty())
+            .try_fold(Self::ROOT, |id, component| self.child(id, component))
+    }
+
+    pub fn name(&self, id: NodeId) -> &str {
+        self.names.resolve(self.name[id as usize])
+    }
+
+    /// The absolute path of `id`, built by walking up to the root.
+    pub fn path(&self, id: NodeId) -> String {
+        let mut components = vec![];
+        let mut node = id;
+        while let Some(parent) = self.parent(node) {
+            components.push(self.name(node));
+            node = parent;
+        }
+        components.reverse();
+        format!("/{}", components.join("/"))
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        (id != Self::ROOT).then(|| self.parent[id as usize])
+    }
+
+    pub fn is_directory(&self, id: NodeId) -> bool {
+        self.file_size[id as usize].is_none()
+    }
+
+    /// The size of a file, or `None` for a directory.
+    pub fn file_size(&self, id: NodeId) -> Option<usize> {
+        self.file_size[id as usize]
+    }
+
+    /// The rank of every node, indexed by id, when all nodes are sorted by path. Paths are
+    /// compared a component at a time, so a directory comes right before its contents.
+    pub fn path_order(&self) -> Vec<u32> {
+        let mut ranks = vec![0; self.len()];
+        let mut stack = vec![Self::ROOT];
+        let mut rank = 0;
+        while let Some(id) = stack.pop() {
+            ranks[id as usize] = rank;
+            rank += 1;
+            let mut children = self.children(id).to_vec();
+            children.sort_by(|&a, &b| self.name(b).cmp(self.name(a)));
+            stack.extend(children);
+        }
+        ranks
+    }
+
+    /// The size of every node, indexed by id. Since children always come after their
+    /// parent, one pass from the last id down adds each node into its parent after all
+    /// of its own children were added into it, a post-order without recursion or a stack.
+    pub fn sizes(&self) -> Vec<usize> {
+        let mut sizes = self
+            .file_size
+            .iter()
+            .map(|size| size.unwrap_or(0))
+            .collect::<Vec<_>>();
+        for id in (1..sizes.len()).rev() {
+            sizes[self.parent[id] as usize] += sizes[id];
+        }
+        sizes
+    }
+}
+
+/// Builds the tree from a well-formed transcript such as a generated one. Only `cd` into
+/// directories already listed is checked; the full validation lives in the binary's `Vfs`.
+impl FromStr for DenseTree {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tree = DenseTree::new();
+        let mut cwd = DenseTree::ROOT;
+        for line in s.lines() {
+            if let Some(command) = line.strip_prefix("$ ") {
+                match command {
+                    "ls" => {}
+                    "cd /" => cwd = DenseTree::ROOT,
+                    "cd .." => cwd = tree.parent(cwd).unwrap_or(DenseTree::ROOT),
+                    _ => {
+                        let name = command
+                            .strip_prefix("cd ")
+                            .ok_or_else(|| format!("unknown command {:?}", line))?;
+                        cwd = tree
+                            .child(cwd, name)
+                            .filter(|&id| tree.is_directory(id))
+                            .ok_or_else(|| format!("no such directory {:?}", name))?;
+                    }
+                }
+            } else {
+                let (kind, name) = line
+                    .split_once(' ')
+                    .ok_or_else(|| format!("invalid ls output {:?}", line))?;
+                let file_size = match kind {
+                    "dir" => None,
+                    size => Some(
+                        size.parse()
+                            .map_err(|_| format!("invalid ls output {:?}", line))?,
+                    ),
+                };
+                tree.add(cwd, name, file_size);
+            }
+        }
+        Ok(tree)
+    }
+}
+
+/// Pre-order walk over a [`DenseTree`], see [`DenseTree::pre_order`].
+pub struct PreOrder<'a> {
+    tree: &'a DenseTree,
+    stack: Vec<NodeId>,
+}
+
+impl Iterator for PreOrder<'_> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = self.stack.pop()?;
+        // pushed last to first so the first child is visited next
+        self.stack
+            .extend(self.tree.children(id).iter().rev().copied());
+        Some(id)
+    }
+}
@@ -0,0 +1,276 @@
+use std::fmt;
+
+use day7::sizes::{DenseTree, NodeId};
+
+use crate::{DirEntry, DirListing};
+
+#[derive(Debug, PartialEq)]
+pub enum VfsError {
+    UnknownDirectory(String),
+    NotADirectory(String),
+    /// A path listed once as a file and once as a directory.
+    ConflictingEntry(String),
+    InvalidListing(String),
+    UnknownCommand(String),
+    /// Directory contents that do not follow an `ls`.
+    UnexpectedOutput(String),
+}
+
+impl fmt::Display for VfsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VfsError::UnknownDirectory(path) => write!(f, "no such directory {}", path),
+            VfsError::NotADirectory(path) => write!(f, "{} is not a directory", path),
+            VfsError::ConflictingEntry(path) => {
+                write!(f, "{} is listed as both a file and a directory", path)
+            }
+            VfsError::InvalidListing(line) => write!(f, "invalid ls output {:?}", line),
+            VfsError::UnknownCommand(line) => write!(f, "unknown command {:?}", line),
+            VfsError::UnexpectedOutput(line) => {
+                write!(f, "output {:?} does not follow a command", line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for VfsError {}
+
+/// A [`VfsError`] with the transcript line it happened on.
+#[derive(Debug, PartialEq)]
+pub struct TranscriptError {
+    pub line_number: usize,
+    pub error: VfsError,
+}
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line_number, self.error)
+    }
+}
+
+impl std::error::Error for TranscriptError {}
+
+/// Resolves `path` against the absolute directory `cwd`, handling absolute paths, `.`,
+/// `..` (which stops at the root) and repeated slashes. Returns an absolute path with no
+/// trailing slash, or `/` for the root.
+pub fn normalise(cwd: &str, path: &str) -> String {
+    let mut components = Vec::new();
+    let base = if path.starts_with('/') { "" } else { cwd };
+    for component in base.split('/').chain(path.split('/')) {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            name => components.push(name),
+        }
+    }
+    format!("/{}", components.join("/"))
+}
+
+/// An in-memory filesystem rebuilt from terminal transcripts, with a current directory.
+/// Nodes are stored in a [`DenseTree`], so a path is only ever spelled out to report it.
+pub struct Vfs {
+    pub tree: DenseTree,
+    cwd: NodeId,
+}
+
+impl Default for Vfs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Vfs {
+    pub fn new() -> Self {
+        Self {
+            tree: DenseTree::new(),
+            cwd: DenseTree::ROOT,
+        }
+    }
+
+    pub fn cwd(&self) -> String {
+        self.tree.path(self.cwd)
+    }
+
+    pub fn resolve(&self, path: &str) -> String {
+        normalise(&self.cwd(), path)
+    }
+
+    /// The node at `path`, resolved like [`normalise`] does: `..` only drops the component
+    /// before it, so it does not matter whether that component exists.
+    fn lookup(&self, path: &str) -> Option<NodeId> {
+        let mut base = if path.starts_with('/') {
+            DenseTree::ROOT
+        } else {
+            self.cwd
+        };
+        let mut names = Vec::new();
+        for component in path.split('/') {
+            match component {
+                "" | "." => {}
+                ".." => {
+                    if names.pop().is_none() {
+                        base = self.tree.parent(base).unwrap_or(DenseTree::ROOT);
+                    }
+                }
+                name => names.push(name),
+            }
+        }
+        names
+            .into_iter()
+            .try_fold(base, |id, name| self.tree.child(id, name))
+    }
+
+    /// The directory at `path`, which has to have been seen in some listing.
+    pub fn directory(&self, path: &str) -> Result<NodeId, VfsError> {
+        match self.lookup(path) {
+            None => Err(VfsError::UnknownDirectory(self.resolve(path))),
+            Some(id) if !self.tree.is_directory(id) => {
+                Err(VfsError::NotADirectory(self.resolve(path)))
+            }
+            Some(id) => Ok(id),
+        }
+    }
+
+    pub fn cd(&mut self, path: &str) -> Result<(), VfsError> {
+        self.cwd = self.directory(path)?;
+        Ok(())
+    }
+
+    /// Records an entry of the current directory. Listing the same entry again does not
+    /// add another node; a file listed again takes the newly reported size.
+    pub fn add_listing(&mut self, listing: DirListing) -> Result<(), VfsError> {
+        let file_size = match listing.dir_entry {
+            DirEntry::FILE(size) => Some(size),
+            DirEntry::DIRECTORY => None,
+        };
+        match self.tree.child(self.cwd, &listing.name) {
+            None => {
+                self.tree.add(self.cwd, &listing.name, file_size);
+                Ok(())
+            }
+            Some(id) => match (self.tree.file_size(id), file_size) {
+                (None, None) => Ok(()),
+                (Some(_), Some(size)) => {
+                    self.tree.set_file_size(id, size);
+                    Ok(())
+                }
+                _ => Err(VfsError::ConflictingEntry(self.resolve(&listing.name))),
+            },
+        }
+    }
+
+    /// Replays a transcript of `cd` and `ls` commands on top of what is already known.
+    pub fn run_transcript(&mut self, transcript: &str) -> Result<(), TranscriptError> {
+        let mut in_listing = false;
+        for (idx, line) in transcript.lines().enumerate() {
+            let at_line = |error| TranscriptError {
+                line_number: idx + 1,
+                error,
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(command) = line.strip_prefix("$ ") {
+                let mut parts = command.split_whitespace();
+                in_listing = false;
+                match (parts.next(), parts.next(), parts.next()) {
+                    (Some("cd"), Some(path), None) => self.cd(path).map_err(at_line)?,
+                    (Some("ls"), None, None) => in_listing = true,
+                    _ => return Err(at_line(VfsError::UnknownCommand(line.into()))),
+                }
+            } else if in_listing {
+                let listing = line.parse().map_err(at_line)?;
+                self.add_listing(listing).map_err(at_line)?;
+            } else {
+                return Err(at_line(VfsError::UnexpectedOutput(line.into())));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Vfs {
+    type Err = TranscriptError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut vfs = Vfs::new();
+        vfs.run_transcript(s)?;
+        Ok(vfs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_at(transcript: &str) -> TranscriptError {
+        transcript.parse::<Vfs>().err().unwrap()
+    }
+
+    #[test]
+    fn cd_root_mid_session() {
+        let vfs: Vfs = "$ ls\ndir a\n$ cd a\n$ ls\n1 x\n$ cd /\n$ ls\n2 y"
+            .parse()
+            .unwrap();
+        assert_eq!(vfs.cwd(), "/");
+        assert!(vfs.tree.id("/y").is_some());
+        assert!(vfs.tree.id("/a/y").is_none());
+    }
+
+    #[test]
+    fn cd_up_at_root() {
+        let vfs: Vfs = "$ cd /\n$ cd ..\n$ cd ../..".parse().unwrap();
+        assert_eq!(vfs.cwd(), "/");
+    }
+
+    #[test]
+    fn relisting_updates_in_place() {
+        let vfs: Vfs = "$ ls\ndir a\n10 f\n$ ls\ndir a\n20 f".parse().unwrap();
+        assert_eq!(vfs.tree.len(), 3);
+        let file = vfs.tree.id("/f").unwrap();
+        assert_eq!(vfs.tree.file_size(file), Some(20));
+    }
+
+    #[test]
+    fn errors_carry_their_line() {
+        assert_eq!(
+            error_at("$ cd /\n\n$ cd missing"),
+            TranscriptError {
+                line_number: 3,
+                error: VfsError::UnknownDirectory("/missing".into()),
+            }
+        );
+        assert_eq!(
+            error_at("$ ls\n5 f\n$ cd f"),
+            TranscriptError {
+                line_number: 3,
+                error: VfsError::NotADirectory("/f".into()),
+            }
+        );
+        assert_eq!(
+            error_at("$ ls\ndir a\n$ cd a\n$ ls\n5 f\ndir f"),
+            TranscriptError {
+                line_number: 6,
+                error: VfsError::ConflictingEntry("/a/f".into()),
+            }
+        );
+        assert_eq!(
+            error_at("$ ls\n5 a/b"),
+            TranscriptError {
+                line_number: 2,
+                error: VfsError::InvalidListing("5 a/b".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn normalise_paths() {
+        assert_eq!(normalise("/a/b", "."), "/a/b");
+        assert_eq!(normalise("/a/b", ".."), "/a");
+        assert_eq!(normalise("/", ".."), "/");
+        assert_eq!(normalise("/a", "b//c/./d/"), "/a/b/c/d");
+        assert_eq!(normalise("/a", "//x/../y"), "/y");
+    }
+}